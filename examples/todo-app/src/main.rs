@@ -10,13 +10,13 @@ use crossterm::{
     },
     terminal::EnterAlternateScreen,
 };
-use mana_tui::{key, mana_tui_beheaded::focus::FocusExt};
 use mana_tui::{
-    mana_tui_beheaded::{
-        self,
-        focus::{FocusPolicy, Keybind, OnClick},
-        setup_interactions,
-    },
+    key,
+    mana_tui_beheaded::{focus::FocusExt, scroll::ScrollStateExt},
+    mana_tui_utils::resource::Resources,
+};
+use mana_tui::{
+    mana_tui_beheaded::{self, focus::Keybind, setup_interactions},
     prelude::*,
 };
 use ratatui::{
@@ -55,6 +55,10 @@ async fn main() -> anyhow::Result<()> {
 async fn app(terminal: &mut DefaultTerminal) -> Result<()> {
     let mut ctx = ElementCtx::new();
     mana_tui_beheaded::init(&mut ctx);
+    ctx.insert_resource(theme());
+    if let Err(err) = ctx.watch_theme(concat!(env!("CARGO_MANIFEST_DIR"), "/theme.toml")) {
+        tracing::warn!(%err, "failed to load theme.toml, using the built-in theme");
+    }
     let r = init(&mut ctx, terminal.get_frame().area());
     ctx.despawn_ui(r);
     loop {
@@ -85,6 +89,16 @@ async fn app(terminal: &mut DefaultTerminal) -> Result<()> {
     }
 }
 
+fn theme() -> Theme {
+    Theme::new()
+        .class("button", Style::new().bg(tw::SKY.c200).fg(Color::Black))
+        .class(
+            "titlebar",
+            Style::new().bg(tw::PURPLE.c300).fg(tw::PURPLE.c300),
+        )
+        .class("titlebar-text", Style::new().fg(Color::Black))
+}
+
 #[derive(Debug, Clone)]
 struct Todo {
     done: bool,
@@ -112,7 +126,9 @@ fn handle_events(ctx: &mut ElementCtx, event: Event) -> bool {
 #[subview]
 fn todo_app(ctx: &mut ElementCtx) -> View {
     struct AddTodoButton;
+    struct TodoList;
     ctx.use_focus::<AddTodoButton>();
+    ctx.use_scroll::<TodoList>();
 
     let mut todos = ctx.query::<&Todo>();
     let todo_count = todos.iter().count();
@@ -122,22 +138,21 @@ fn todo_app(ctx: &mut ElementCtx) -> View {
                 <Titlebar/>
                 <Block Padding::new(2, 2, 1, 2) Width::grow() Height::grow()>
                     <Text
-                        .style={Style::new().bg(tw::SKY.c200).fg(Color::Black)}
+                        Class("button")
                         AddTodoButton
-                        FocusPolicy::Block
                         Keybind::new(KeyCode::Char('a'))
-                        OnClick::new(move |world| {
+                        @click={move |world| {
                             // we throw our state into the ECS world :)
                             world.spawn((Todo {
                                 done: false,
                                 description: format!("{} i crave productivity", todo_count + 1),
                             },));
-                        })
+                        }}
                     >
                         "(a) add todo"
                     </Text>
                     <Block .borders={Borders::TOP} .border_type={BorderType::LightDoubleDashed} Width::grow() Height::fixed(1)/>
-                    <Block Gap(1) ScrollView::default() ScrollViewState::new() Width::grow()>
+                    <Block Gap(1) TodoList Scroll::vertical() Width::grow() Height::grow()>
                     {
                         todos.iter().map(|todo| ui! {
                             <TodoItem .todo={todo}/>
@@ -160,15 +175,13 @@ fn titlebar() -> View {
             //     Style::new().fg(tw::PURPLE.c300).bg(Color::Reset)
             // }
             // .borders={Borders::LEFT | Borders::RIGHT | Borders::TOP }
-            .style={
-                Style::new().bg(tw::PURPLE.c300).fg(tw::PURPLE.c300)
-            }
+            Class("titlebar")
             Direction::Horizontal
             MainJustify::SpaceBetween
             Width::grow() Height::fixed(1)
         >
-            <Paragraph .style={Style::new().fg(Color::Black)}>"{title_icon} +++ Mana To Do +++"</Paragraph>
-            <Text .style={Style::new().fg(Color::Black)}>"q {title_icon}"</Text>
+            <Paragraph Class("titlebar-text")>"{title_icon} +++ Mana To Do +++"</Paragraph>
+            <Text Class("titlebar-text")>"q {title_icon}"</Text>
         </Block>
     }
 }
@@ -176,22 +189,13 @@ fn titlebar() -> View {
 #[subview]
 fn todo_item(todo: &Todo) -> View {
     let Todo { done, description } = todo;
-
-    #[subview]
-    fn checkbox(done: bool) -> View {
-        if done {
-            ui! {
-                <Text>"[x]"</Text>
-            }
-        } else {
-            ui! {
-                <Text>"[ ]"</Text>
-            }
-        }
-    }
     ui! {
         <Block Direction::Horizontal Gap(1) Width::grow()>
-            <Checkbox .done={*done}/>
+            {#if *done {
+                <Text>"[x]"</Text>
+            } else {
+                <Text>"[ ]"</Text>
+            }}
             "{description}"
         </Block>
     }
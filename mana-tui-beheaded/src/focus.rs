@@ -6,6 +6,8 @@ use hecs::{CommandBuffer, Component, Or};
 use hecs::{Entity, World};
 use mana_tui_elemental::layout::Children;
 use mana_tui_elemental::layout::Props;
+use mana_tui_elemental::layout::{is_hidden, rendered_area};
+use mana_tui_elemental::scroll::ScrollExt;
 use mana_tui_utils::Ecs;
 use mana_tui_utils::resource::Resources;
 use mana_tui_utils::systems::SystemsExt;
@@ -13,8 +15,7 @@ use ratatui::crossterm::event::{
     KeyCode, KeyEventKind, KeyEventState, KeyModifiers, MouseEventKind,
 };
 use ratatui::crossterm::event::{KeyEvent, MouseEvent};
-use ratatui::layout::Position;
-use ratatui::layout::Rect;
+use ratatui::layout::{Offset, Position};
 use ratatui::style::{Modifier, Style};
 use smallvec::SmallVec;
 
@@ -44,6 +45,9 @@ pub(crate) fn generate_ui_stack(world: &mut World, root: Entity) {
 }
 
 pub(crate) fn generate_ui_stack_impl(world: &World, root: Entity, stack: &mut Vec<Entity>) {
+    if is_hidden(world, root) {
+        return;
+    }
     stack.push(root);
     let children = world.get::<&Children>(root);
     if let Ok(children) = children {
@@ -75,27 +79,40 @@ pub(crate) fn handle_mouse_event(
         uistack.stack.clone()
     };
 
+    let position = Position {
+        x: event.column,
+        y: event.row,
+    };
+    let scroll = match event.kind {
+        MouseEventKind::ScrollUp => Some(Offset { x: 0, y: -1 }),
+        MouseEventKind::ScrollDown => Some(Offset { x: 0, y: 1 }),
+        MouseEventKind::ScrollLeft => Some(Offset { x: -1, y: 0 }),
+        MouseEventKind::ScrollRight => Some(Offset { x: 1, y: 0 }),
+        _ => None,
+    };
+    // the wheel scrolls the container under the cursor, and is dispatched like any other event
+    // everywhere else
+    if let Some(delta) = scroll
+        && world.scroll_at(position, delta)
+    {
+        return Ok(true);
+    }
+
     let mut consumed = false;
 
     for entity in uistack.iter().rev().copied() {
-        let mut query_one = world.query_one::<(&Props, Option<&FocusPolicy>, &Marker)>(entity);
-        let Ok((props, focus_policy, &marker)) = query_one.get() else {
+        let mut query_one = world.query_one::<(Option<&FocusPolicy>, Option<&Marker>)>(entity);
+        let Ok((focus_policy, marker)) = query_one.get() else {
             continue;
         };
-        let props = *props;
+        let marker = marker.copied();
         let focus_policy = *focus_policy.unwrap_or(&FocusPolicy::Pass);
-        let area = Rect {
-            x: props.position.x,
-            y: props.position.y,
-            width: props.size.x,
-            height: props.size.y,
-        };
         drop(query_one);
+        let Some(area) = rendered_area(world, entity) else {
+            continue;
+        };
 
-        if !area.contains(Position {
-            x: event.column,
-            y: event.row,
-        }) {
+        if !area.contains(position) {
             continue;
         }
 
@@ -104,13 +121,16 @@ pub(crate) fn handle_mouse_event(
                 FocusPolicy::Block => {
                     world.insert_one(entity, Clicked)?;
                     consumed = true;
-                    tokio::task::spawn(async move {
-                        tokio::time::sleep(Duration::from_millis(100)).await;
-                        _ = queue
-                            .0
-                            .send_async(crate::UiEvent::ClickedStyleFinished(marker.0))
-                            .await;
-                    });
+                    // only elements with a focus style have one to restore
+                    if let Some(marker) = marker {
+                        tokio::task::spawn(async move {
+                            tokio::time::sleep(Duration::from_millis(100)).await;
+                            _ = queue
+                                .0
+                                .send_async(crate::UiEvent::ClickedStyleFinished(marker.0))
+                                .await;
+                        });
+                    }
                     break;
                 }
                 FocusPolicy::Pass => {}
@@ -157,6 +177,7 @@ impl OnClick {
     }
 }
 
+/// runs when the element's [`Keybind`] is pressed, after its [`OnClick`].
 #[derive(derive_more::Deref, derive_more::DerefMut, Clone)]
 pub struct OnPress(Arc<dyn Fn(&mut World) + Send + Sync + 'static>);
 
@@ -166,6 +187,16 @@ impl OnPress {
     }
 }
 
+/// runs while the mouse moves over an element with [`FocusPolicy::Block`].
+#[derive(derive_more::Deref, derive_more::DerefMut, Clone)]
+pub struct OnHover(Arc<dyn Fn(&mut World) + Send + Sync + 'static>);
+
+impl OnHover {
+    pub fn new(func: impl Fn(&mut World) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(func) as Arc<_>)
+    }
+}
+
 pub(crate) fn on_click_system(world: &mut World) {
     let handlers = world
         .query_mut::<(&OnClick, &Clicked)>()
@@ -177,6 +208,17 @@ pub(crate) fn on_click_system(world: &mut World) {
     }
 }
 
+pub(crate) fn on_hover_system(world: &mut World) {
+    let handlers = world
+        .query_mut::<(&OnHover, &Hovered)>()
+        .into_iter()
+        .map(|(handler, _)| handler.0.clone())
+        .collect::<Vec<_>>();
+    for handler in handlers {
+        handler(world);
+    }
+}
+
 pub struct Keybind(KeyEvent);
 
 impl Keybind {
@@ -220,17 +262,19 @@ pub(crate) fn keybind_clicked_system(world: &mut World, event: KeyEvent) -> bool
     let queue = world.get_resource::<&EventQueue>().unwrap().0.clone();
     let mut cmd = CommandBuffer::new();
     let mut consumed = false;
-    for (entity, keybind, &marker) in world.query_mut::<(Entity, &Keybind, &Marker)>() {
+    for (entity, keybind, marker) in world.query_mut::<(Entity, &Keybind, Option<&Marker>)>() {
         if keybind.event_eq(event, KeyEventKind::Press) {
             consumed = true;
             cmd.insert_one(entity, Pressed);
-            let tx = queue.0.clone();
-            tokio::task::spawn(async move {
-                tokio::time::sleep(Duration::from_millis(100)).await;
-                _ = tx
-                    .send_async(crate::UiEvent::ClickedStyleFinished(marker.0))
-                    .await;
-            });
+            if let Some(&marker) = marker {
+                let tx = queue.0.clone();
+                tokio::task::spawn(async move {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    _ = tx
+                        .send_async(crate::UiEvent::ClickedStyleFinished(marker.0))
+                        .await;
+                });
+            }
         } else if keybind.event_eq(event, KeyEventKind::Release) {
             cmd.remove_one::<Pressed>(entity);
         }
@@ -22,6 +22,7 @@ use crate::schedule::PreRenderSchedule;
 
 pub mod focus;
 pub mod schedule;
+pub mod scroll;
 
 pub fn handle_event(mut world: &mut World, event: Event) -> bool {
     match event {
@@ -38,6 +39,7 @@ pub fn handle_event(mut world: &mut World, event: Event) -> bool {
             focus::clear_old_hovers(world);
             let consumed = focus::handle_mouse_event(world, mouse_event);
             focus::on_click_system(world);
+            focus::on_hover_system(world);
             world.run_systems::<PostRenderSchedule>();
             focus::click_post_update_system(world);
             if consumed == Ok(true) {
@@ -0,0 +1,70 @@
+use std::any::TypeId;
+
+use hecs::{Component, Entity, World};
+use mana_tui_elemental::layout::ScrollViewState;
+use mana_tui_elemental::virtual_list::VirtualListState;
+use mana_tui_utils::Ecs;
+use mana_tui_utils::resource::Resources;
+use mana_tui_utils::systems::SystemsExt;
+
+use crate::Store;
+use crate::focus::Transient;
+
+/// scroll offset of a [`Scroll`](mana_tui_elemental::scroll::Scroll) container or
+/// [`VirtualList`](mana_tui_elemental::virtual_list::VirtualList), kept across frames by
+/// [`ScrollStateExt::use_scroll`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ScrollState {
+    view: Option<ScrollViewState>,
+    list: Option<VirtualListState>,
+}
+
+impl Transient for ScrollState {
+    fn restore<T: Component>(world: &mut World) {
+        let Some((entity, _)) = world.query_mut::<(Entity, &T)>().into_iter().next() else {
+            return;
+        };
+        let store = world.get_resource::<&Store<ScrollState>>().unwrap();
+        let Some(ScrollState { view, list }) = store.get(&TypeId::of::<T>()).cloned() else {
+            return;
+        };
+        drop(store);
+        if let Some(view) = view {
+            _ = world.insert_one(entity, view);
+        }
+        if let Some(list) = list {
+            _ = world.insert_one(entity, list);
+        }
+    }
+
+    fn update<T: Component>(world: &mut World) {
+        let Some((_, _, view, list)) = world
+            .query_mut::<(
+                Entity,
+                &T,
+                Option<&ScrollViewState>,
+                Option<&VirtualListState>,
+            )>()
+            .into_iter()
+            .next()
+        else {
+            return;
+        };
+        let state = ScrollState {
+            view: view.copied(),
+            list: list.copied(),
+        };
+        let mut store = world.get_resource::<&mut Store<ScrollState>>().unwrap();
+        store.insert(TypeId::of::<T>(), state);
+    }
+}
+
+pub trait ScrollStateExt: Ecs + SystemsExt {
+    /// keeps the scroll offset of the container or virtual list tagged with `T` when the view is
+    /// respawned.
+    fn use_scroll<T: Component>(&mut self) {
+        ScrollState::hook_transient::<T, Self>(self);
+    }
+}
+
+impl<T: Ecs> ScrollStateExt for T {}
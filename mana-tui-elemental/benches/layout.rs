@@ -0,0 +1,189 @@
+//! benchmarks for the stages of a frame on synthetic trees.
+//!
+//! run with `cargo bench -p mana-tui-elemental`. `frame` measures a whole frame, spawn to despawn,
+//! which has to stay under 16ms for 60 fps.
+
+use std::hint::black_box;
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use mana_tui_elemental::prelude::*;
+use ratatui::{buffer::Buffer, layout::Rect};
+
+const AREA: Rect = Rect::new(0, 0, 120, 40);
+
+const LOREM: &str = "lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor \
+    incididunt ut labore et dolore magna aliqua. ut enim ad minim veniam, quis nostrud \
+    exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat.";
+
+const TREES: [(&str, fn() -> View); 5] = [
+    ("wide", wide),
+    ("deep", deep),
+    ("grid", grid),
+    ("text_heavy", text_heavy),
+    ("table_5k", table_5k),
+];
+
+/// one container with 5000 lines of text.
+fn wide() -> View {
+    ui(Block::new())
+        .with((Width::grow(), Height::grow()))
+        .children((0..5000).map(|idx| ui(Text::raw(format!("item {idx}"))).done()))
+        .done()
+}
+
+/// 200 nested containers.
+fn deep() -> View {
+    (0..200).fold(ui(Text::raw("leaf")).done(), |child, idx| {
+        let direction = if idx % 2 == 0 {
+            Direction::Vertical
+        } else {
+            Direction::Horizontal
+        };
+        ui(Block::new()).with((direction,)).child(child).done()
+    })
+}
+
+/// 50 rows of 40 bordered cells that grow to share the width.
+fn grid() -> View {
+    let cell = |idx: usize| {
+        ui(Block::bordered())
+            .with((Width::grow(),))
+            .child(ui(Text::raw(format!("{idx:02}"))))
+            .done()
+    };
+    let row = |_| {
+        ui(Block::new())
+            .with((Direction::Horizontal, Width::grow()))
+            .children((0..40).map(cell))
+            .done()
+    };
+    ui(Block::new())
+        .with((Width::grow(), Height::grow()))
+        .children((0..50).map(row))
+        .done()
+}
+
+/// 500 wrapped paragraphs.
+fn text_heavy() -> View {
+    ui(Block::new())
+        .with((Width::grow(), Height::grow(), Gap(1)))
+        .children((0..500).map(|_| {
+            ui(Paragraph::new(LOREM).wrap(Wrap { trim: true }))
+                .with((Width::grow(),))
+                .done()
+        }))
+        .done()
+}
+
+/// a table with 5000 rows of 4 columns.
+fn table_5k() -> View {
+    let row = |idx: usize| {
+        ui(Block::new())
+            .with((Direction::Horizontal, Gap(1)))
+            .children((
+                ui(Text::raw(format!("{idx}"))).with((Width::fixed(6),)),
+                ui(Text::raw(format!("row {idx}"))).with((Width::fixed(20),)),
+                ui(Text::raw("description")).with((Width::grow(),)),
+                ui(Text::raw(format!("{}", idx * 7 % 100))).with((Width::fixed(4),)),
+            ))
+            .done()
+    };
+    ui(Block::new())
+        .with((Width::grow(), Height::grow()))
+        .children((0..5000).map(row))
+        .done()
+}
+
+fn spawned(tree: fn() -> View) -> (ElementCtx, Element) {
+    let mut ctx = ElementCtx::new();
+    let root = ctx.spawn_ui(tree());
+    (ctx, root)
+}
+
+fn bench_spawn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spawn_ui");
+    for (name, tree) in TREES {
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || (ElementCtx::new(), tree()),
+                |(mut ctx, view)| {
+                    let root = ctx.spawn_ui(view);
+                    (ctx, root)
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+fn bench_layout(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calculate_layout");
+    for (name, tree) in TREES {
+        group.bench_function(name, |b| {
+            let (mut ctx, root) = spawned(tree);
+            b.iter(|| ctx.calculate_layout(black_box(root), AREA).unwrap());
+        });
+    }
+    group.finish();
+}
+
+fn bench_render(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render");
+    for (name, tree) in TREES {
+        group.bench_function(name, |b| {
+            let (mut ctx, root) = spawned(tree);
+            ctx.calculate_layout(root, AREA).unwrap();
+            let mut buf = Buffer::empty(AREA);
+            b.iter(|| {
+                buf.reset();
+                ctx.render(black_box(root), AREA, &mut buf);
+            });
+        });
+    }
+    group.finish();
+}
+
+fn bench_despawn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("despawn_ui");
+    for (name, tree) in TREES {
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || spawned(tree),
+                |(mut ctx, root)| {
+                    ctx.despawn_ui(root);
+                    ctx
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+fn bench_frame(c: &mut Criterion) {
+    let mut group = c.benchmark_group("frame");
+    for (name, tree) in TREES {
+        group.bench_function(name, |b| {
+            let mut ctx = ElementCtx::new();
+            let mut buf = Buffer::empty(AREA);
+            b.iter(|| {
+                let root = ctx.spawn_ui(tree());
+                ctx.calculate_layout(root, AREA).unwrap();
+                ctx.render(root, AREA, &mut buf);
+                ctx.despawn_ui(root);
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_spawn,
+    bench_layout,
+    bench_render,
+    bench_despawn,
+    bench_frame
+);
+criterion_main!(benches);
@@ -0,0 +1,446 @@
+//! # Animation
+//!
+//! transitions that animate elements between frames.
+//!
+//! an element with a [`Transition`] does not jump to a new size, position or style. when one of
+//! them changes between two frames, the element moves from the value it was showing to the new
+//! one over the duration of the transition. sizes and positions move forward in
+//! [`ElementCtx::calculate_layout`], sizes before the children are positioned in them. styles move
+//! forward in [`ElementCtx::render`], which draws the colors of the transition over the ones of
+//! the widget without changing its style. time is read from the
+//! [`Time`] resource, which the app advances every frame, so tests can step it exactly.
+//!
+//! elements are matched between frames by their place in the tree, so views that are respawned
+//! on every update animate as well.
+//!
+//! # Example
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use mana_tui_elemental::prelude::*;
+//! # use mana_tui_utils::resource::Resources;
+//! # use ratatui::{buffer::Buffer, layout::Rect};
+//! let view = |width| {
+//!     ui(Block::bordered()).with((
+//!         Transition::new(Property::Size, Duration::from_millis(200)),
+//!         Width::fixed(width),
+//!         Height::fixed(3),
+//!     ))
+//! };
+//!
+//! let mut ctx = ElementCtx::new();
+//! ctx.insert_resource(Time::default());
+//! let root = ctx.spawn_ui(view(10));
+//! # let mut buf = Buffer::empty(Rect::new(0, 0, 40, 3));
+//! ctx.calculate_layout(root, buf.area).unwrap();
+//! ctx.render(root, buf.area, &mut buf);
+//!
+//! // the block grows from 10 to 30 cells over the next 200ms
+//! ctx.despawn_ui(root);
+//! let root = ctx.spawn_ui(view(30));
+//! ctx.get_resource::<&mut Time>().unwrap().advance(Duration::from_millis(100));
+//! ctx.calculate_layout(root, buf.area).unwrap();
+//! ctx.render(root, buf.area, &mut buf);
+//! assert!(ctx.is_animating());
+//! ```
+
+use std::{collections::HashMap, time::Duration};
+
+use derive_more as d;
+use glam::{IVec2, U16Vec2};
+use mana_tui_utils::resource::Resources;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+};
+
+use crate::color::{self, ColorDepth};
+use crate::layout::{Children, Element, ElementCtx, Props};
+
+/// resource that holds the current time of the ui. transitions only move forward when it does.
+///
+/// apps usually set it to the time since they started before every frame, with
+/// `ctx.insert_or_update_resource(Time(start.elapsed()))`. without it, transitions finish
+/// instantly.
+/// defaults to: `Time(Duration::ZERO)`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, d::Deref)]
+pub struct Time(pub Duration);
+
+impl Time {
+    /// moves the time forward by `delta`.
+    pub fn advance(&mut self, delta: Duration) {
+        self.0 += delta;
+    }
+}
+
+/// the values of an element a [`Transition`] animates.
+/// defaults to: `Property::All`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Property {
+    /// size, position and style.
+    #[default]
+    All,
+    /// size and position.
+    Layout,
+    /// the size of the element.
+    Size,
+    /// the position of the element. its children move along with it.
+    Position,
+    /// the foreground and background colors of the element. other style changes apply at once.
+    Style,
+}
+
+impl Property {
+    fn includes(self, other: Property) -> bool {
+        match self {
+            Property::All => true,
+            Property::Layout => matches!(other, Property::Size | Property::Position),
+            property => property == other,
+        }
+    }
+}
+
+/// how a [`Transition`] moves between the old and the new value over time.
+/// defaults to: `Easing::EaseInOut`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Easing {
+    /// constant speed.
+    Linear,
+    /// starts slow and speeds up.
+    EaseIn,
+    /// starts fast and slows down.
+    EaseOut,
+    /// starts and ends slow.
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// maps the linear progress `t` of a transition to the eased one, both from 0 to 1.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (2.0 - 2.0 * t).powi(3) / 2.0,
+        }
+    }
+}
+
+/// animates changes to the size, position or style of an element.
+/// defaults to: `Transition { property: Property::All, duration: 200ms, easing: Easing::EaseInOut }`
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// # use mana_tui_elemental::prelude::*;
+/// ui(Block::bordered()).with((
+///     Transition::new(Property::Style, Duration::from_millis(150)).easing(Easing::Linear),
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Transition {
+    /// the values that are animated.
+    pub property: Property,
+    /// how long it takes to reach a new value.
+    pub duration: Duration,
+    /// how the value moves over time.
+    pub easing: Easing,
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Self::new(Property::All, Duration::from_millis(200))
+    }
+}
+
+impl Transition {
+    /// create a transition for `property` with the default easing.
+    pub const fn new(property: Property, duration: Duration) -> Self {
+        Self {
+            property,
+            duration,
+            easing: Easing::EaseInOut,
+        }
+    }
+
+    /// sets the easing of the transition.
+    #[must_use]
+    pub const fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// linear progress after `elapsed`, from 0 to 1.
+    fn progress(&self, elapsed: Duration) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+}
+
+/// values that can be animated.
+trait Tweenable: Copy + PartialEq {
+    fn lerp(from: Self, to: Self, t: f32, depth: ColorDepth) -> Self;
+}
+
+impl Tweenable for U16Vec2 {
+    fn lerp(from: Self, to: Self, t: f32, _: ColorDepth) -> Self {
+        from.as_vec2().lerp(to.as_vec2(), t).round().as_u16vec2()
+    }
+}
+
+impl Tweenable for IVec2 {
+    fn lerp(from: Self, to: Self, t: f32, _: ColorDepth) -> Self {
+        from.as_vec2().lerp(to.as_vec2(), t).round().as_ivec2()
+    }
+}
+
+impl Tweenable for Style {
+    fn lerp(from: Self, to: Self, t: f32, depth: ColorDepth) -> Self {
+        let lerp_color = |from: Option<Color>, to: Option<Color>| match (
+            from.and_then(color::to_rgb),
+            to.and_then(color::to_rgb),
+        ) {
+            (Some(from), Some(to)) => Some(depth.convert(color::mix(from, to, t))),
+            _ => to,
+        };
+        Style {
+            fg: lerp_color(from.fg, to.fg),
+            bg: lerp_color(from.bg, to.bg),
+            ..to
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Tween<T> {
+    from: T,
+    to: T,
+    shown: T,
+    start: Duration,
+}
+
+impl<T: Tweenable> Tween<T> {
+    fn new(value: T) -> Self {
+        Self {
+            from: value,
+            to: value,
+            shown: value,
+            start: Duration::ZERO,
+        }
+    }
+
+    /// moves towards `target`, starting over from the shown value when the target changes.
+    fn step(
+        &mut self,
+        target: T,
+        transition: &Transition,
+        now: Option<Duration>,
+        depth: ColorDepth,
+    ) -> T {
+        if target != self.to {
+            self.from = self.shown;
+            self.to = target;
+            self.start = now.unwrap_or_default();
+        }
+        let progress = now.map_or(1.0, |now| {
+            transition.progress(now.saturating_sub(self.start))
+        });
+        self.shown = if progress >= 1.0 {
+            self.to
+        } else {
+            T::lerp(self.from, self.to, transition.easing.apply(progress), depth)
+        };
+        self.shown
+    }
+
+    fn is_done(&self) -> bool {
+        self.shown == self.to
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct ElementTweens {
+    size: Option<Tween<U16Vec2>>,
+    position: Option<Tween<IVec2>>,
+    style: Option<Tween<Style>>,
+}
+
+impl ElementTweens {
+    fn is_done(&self) -> bool {
+        self.size.is_none_or(|tween| tween.is_done())
+            && self.position.is_none_or(|tween| tween.is_done())
+            && self.style.is_none_or(|tween| tween.is_done())
+    }
+}
+
+/// resource with the running animations.
+#[derive(Debug, Default)]
+struct Animations {
+    /// keyed by the child indices that lead to an element.
+    tweens: HashMap<Vec<usize>, ElementTweens>,
+    /// the style of the widget and the one shown instead this frame, for elements whose style
+    /// is still moving.
+    styles: HashMap<Element, (Style, Style)>,
+}
+
+/// state of one pass over the tree. sizes and positions are animated while the layout is
+/// calculated and styles before they are rendered, `scope` is the one this pass animates.
+struct AnimationPass {
+    scope: Property,
+    previous: HashMap<Vec<usize>, ElementTweens>,
+    next: HashMap<Vec<usize>, ElementTweens>,
+    now: Option<Duration>,
+    depth: ColorDepth,
+    styles: HashMap<Element, (Style, Style)>,
+}
+
+impl AnimationPass {
+    fn animate<T: Tweenable>(
+        &mut self,
+        slot: &mut Option<Tween<T>>,
+        transition: Option<Transition>,
+        property: Property,
+        target: T,
+    ) -> T {
+        let Some(transition) = transition.filter(|t| t.property.includes(property)) else {
+            *slot = None;
+            return target;
+        };
+        let tween = slot.get_or_insert_with(|| Tween::new(target));
+        tween.step(target, &transition, self.now, self.depth)
+    }
+}
+
+impl ElementCtx {
+    /// whether a [`Transition`] or an effect is still running. apps should keep rendering frames
+    /// until this returns `false`.
+    pub fn is_animating(&self) -> bool {
+        let animating = self
+            .world
+            .get_resource::<&Animations>()
+            .is_ok_and(|animations| !animations.tweens.values().all(ElementTweens::is_done));
+        #[cfg(feature = "fx")]
+        let animating = animating || self.has_running_fx();
+        animating
+    }
+
+    /// advances the transitions of `scope` in the tree of `root`, replacing the laid out sizes and
+    /// positions with the ones to show this frame, or keeping the styles to show for
+    /// [`ElementCtx::overlay_animated_style`].
+    pub(crate) fn animate(&mut self, root: Element, scope: Property) {
+        let has_transitions = self.world.query::<&Transition>().iter().next().is_some();
+        if !has_transitions && self.world.get_resource::<&Animations>().is_err() {
+            return;
+        }
+        let previous = std::mem::take(
+            &mut self
+                .world
+                .get_or_insert_resource_with::<&mut Animations>(Animations::default)
+                .tweens,
+        );
+        let mut pass = AnimationPass {
+            scope,
+            previous,
+            next: HashMap::new(),
+            now: self.world.get_resource::<&Time>().ok().map(|time| time.0),
+            depth: self
+                .world
+                .get_resource::<&ColorDepth>()
+                .map_or_else(|_| ColorDepth::default(), |depth| *depth),
+            styles: HashMap::new(),
+        };
+        self.animate_element(root, &mut Vec::new(), IVec2::ZERO, &mut pass);
+
+        let mut animations = self.world.get_resource::<&mut Animations>().unwrap();
+        animations.tweens = pass.next;
+        if scope.includes(Property::Style) {
+            animations.styles = pass.styles;
+        }
+    }
+
+    /// recolors the cells `element` drew in `area` with the colors its [`Transition`] shows this
+    /// frame. only cells in the colors of the widget's own style are changed, so colors set
+    /// inside the widget stay.
+    pub(crate) fn overlay_animated_style(&self, element: Element, area: Rect, buf: &mut Buffer) {
+        let Ok(animations) = self.world.get_resource::<&Animations>() else {
+            return;
+        };
+        let Some(&(target, shown)) = animations.styles.get(&element) else {
+            return;
+        };
+        for position in area.intersection(buf.area).positions() {
+            let cell = &mut buf[position];
+            if let (Some(target), Some(shown)) = (target.fg, shown.fg)
+                && cell.fg == target
+            {
+                cell.fg = shown;
+            }
+            if let (Some(target), Some(shown)) = (target.bg, shown.bg)
+                && cell.bg == target
+            {
+                cell.bg = shown;
+            }
+        }
+    }
+
+    /// `shift` is how far the ancestors of the element were moved from their laid out position.
+    fn animate_element(
+        &self,
+        element: Element,
+        path: &mut Vec<usize>,
+        shift: IVec2,
+        pass: &mut AnimationPass,
+    ) {
+        let Ok(mut props) = self.world.get::<&mut Props>(element) else {
+            return;
+        };
+        let transition = self.world.get::<&Transition>(element).ok().map(|t| *t);
+        let mut tweens = pass.previous.remove(path.as_slice()).unwrap_or_default();
+
+        let mut shift = shift;
+
+        if pass.scope.includes(Property::Size) {
+            props.size = pass.animate(&mut tweens.size, transition, Property::Size, props.size);
+        }
+        if pass.scope.includes(Property::Position) {
+            let laid_out = props.position.as_ivec2();
+            let position = pass.animate(
+                &mut tweens.position,
+                transition,
+                Property::Position,
+                laid_out + shift,
+            );
+            props.position = position.max(IVec2::ZERO).as_u16vec2();
+            shift = position - laid_out;
+        }
+        if pass.scope.includes(Property::Style)
+            && let Some(target) = (props.get_style)(&self.world, element)
+        {
+            let style = pass.animate(&mut tweens.style, transition, Property::Style, target);
+            if style != target {
+                pass.styles.insert(element, (target, style));
+            }
+        }
+        drop(props);
+
+        if transition.is_some() {
+            pass.next.insert(path.clone(), tweens);
+        }
+
+        let Ok(children) = self.world.get::<&Children>(element).map(|c| c.clone()) else {
+            return;
+        };
+        for (idx, &child) in children.iter().enumerate() {
+            path.push(idx);
+            self.animate_element(child, path, shift, pass);
+            path.pop();
+        }
+    }
+}
@@ -0,0 +1,475 @@
+//! # Code Block
+//!
+//! syntax highlighted source code.
+//!
+//! [`CodeBlock`] styles its source once, when it is created, using a [`Highlighter`]. the
+//! [`BuiltinHighlighter`] knows Rust, JSON and TOML; other languages are shown as plain text
+//! unless a custom highlighter is used. code blocks are sized to their content, like [`Text`],
+//! and their tabs are expanded with the [`TabWidth`] resource when they are spawned.
+//!
+//! # Example
+//!
+//! ```
+//! # use mana_tui_elemental::prelude::*;
+//! # use ratatui::{buffer::Buffer, layout::Rect};
+//! let mut ctx = ElementCtx::new();
+//! let code = CodeBlock::new("fn main() {\n    println!(\"hi\");\n}", "rust")
+//!     .line_numbers(true)
+//!     .highlight_lines(2..=2);
+//! let root = ctx.spawn_ui(ui(code));
+//!
+//! # let mut buf = Buffer::empty(Rect::new(0, 0, 24, 3));
+//! ctx.calculate_layout(root, buf.area).unwrap();
+//! ctx.render(root, buf.area, &mut buf);
+//! ```
+
+use std::ops::RangeInclusive;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style, Styled},
+    text::{Line, Span, Text},
+    widgets::Widget,
+};
+
+use crate::unicode::{self, TabWidth};
+
+/// turns source code into styled text.
+pub trait Highlighter {
+    /// styles `source` as `language`. returns `None` if the language is not supported.
+    fn highlight(&self, source: &str, language: &str) -> Option<Text<'static>>;
+}
+
+/// kinds of tokens found by the [`BuiltinHighlighter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// reserved words like `fn` and `let`, and lifetimes.
+    Keyword,
+    /// type names. in Rust, identifiers starting with an uppercase letter.
+    Type,
+    /// functions and macros being called.
+    Function,
+    /// string and character literals.
+    String,
+    /// number literals.
+    Number,
+    /// `true`, `false`, `null` and similar constants.
+    Literal,
+    /// keys in JSON objects and TOML tables, and TOML table headers.
+    Key,
+    /// comments.
+    Comment,
+    /// anything else.
+    Plain,
+}
+
+/// styles used by the [`BuiltinHighlighter`] for every [`TokenKind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HighlightStyles {
+    /// style of [`TokenKind::Keyword`].
+    pub keyword: Style,
+    /// style of [`TokenKind::Type`].
+    pub ty: Style,
+    /// style of [`TokenKind::Function`].
+    pub function: Style,
+    /// style of [`TokenKind::String`].
+    pub string: Style,
+    /// style of [`TokenKind::Number`].
+    pub number: Style,
+    /// style of [`TokenKind::Literal`].
+    pub literal: Style,
+    /// style of [`TokenKind::Key`].
+    pub key: Style,
+    /// style of [`TokenKind::Comment`].
+    pub comment: Style,
+    /// style of [`TokenKind::Plain`].
+    pub plain: Style,
+}
+
+impl Default for HighlightStyles {
+    fn default() -> Self {
+        Self {
+            keyword: Style::new().fg(Color::Magenta),
+            ty: Style::new().fg(Color::Yellow),
+            function: Style::new().fg(Color::Blue),
+            string: Style::new().fg(Color::Green),
+            number: Style::new().fg(Color::Cyan),
+            literal: Style::new().fg(Color::Cyan),
+            key: Style::new().fg(Color::Blue),
+            comment: Style::new()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::ITALIC),
+            plain: Style::new(),
+        }
+    }
+}
+
+impl HighlightStyles {
+    /// the style of a kind of token.
+    pub fn get(&self, kind: TokenKind) -> Style {
+        match kind {
+            TokenKind::Keyword => self.keyword,
+            TokenKind::Type => self.ty,
+            TokenKind::Function => self.function,
+            TokenKind::String => self.string,
+            TokenKind::Number => self.number,
+            TokenKind::Literal => self.literal,
+            TokenKind::Key => self.key,
+            TokenKind::Comment => self.comment,
+            TokenKind::Plain => self.plain,
+        }
+    }
+}
+
+/// a small lexer for Rust (`rust`, `rs`), JSON (`json`) and TOML (`toml`).
+///
+/// it only looks at single tokens, so it is fast and never fails, but it does not understand
+/// the structure of the code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BuiltinHighlighter {
+    /// styles of each kind of token.
+    pub styles: HighlightStyles,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    Rust,
+    Json,
+    Toml,
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "type", "unsafe", "use",
+    "where", "while", "yield",
+];
+
+impl Language {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "rust" | "rs" => Some(Self::Rust),
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    fn is_ident_char(self, ch: char) -> bool {
+        ch.is_alphanumeric() || ch == '_' || (self == Self::Toml && ch == '-')
+    }
+
+    /// `in_key` is whether the token is on the left of a TOML `=`.
+    fn classify(self, ident: &str, rest: &str, in_key: bool) -> TokenKind {
+        let next = rest.trim_start_matches([' ', '\t']).chars().next();
+        match self {
+            Self::Rust if RUST_KEYWORDS.contains(&ident) => TokenKind::Keyword,
+            Self::Rust if matches!(ident, "true" | "false") => TokenKind::Literal,
+            Self::Rust if matches!(next, Some('(' | '!')) => TokenKind::Function,
+            Self::Rust if ident.starts_with(char::is_uppercase) => TokenKind::Type,
+            Self::Json if matches!(ident, "true" | "false" | "null") => TokenKind::Literal,
+            Self::Toml if in_key => TokenKind::Key,
+            Self::Toml if matches!(ident, "true" | "false" | "inf" | "nan") => TokenKind::Literal,
+            _ => TokenKind::Plain,
+        }
+    }
+}
+
+impl Highlighter for BuiltinHighlighter {
+    fn highlight(&self, source: &str, language: &str) -> Option<Text<'static>> {
+        let language = Language::from_name(language)?;
+        let mut lines = vec![Line::default()];
+        for (kind, token) in tokenize(source, language) {
+            let style = self.styles.get(kind);
+            // tokens like block comments can span several lines
+            for (idx, part) in token.split('\n').enumerate() {
+                if idx > 0 {
+                    lines.push(Line::default());
+                }
+                if part.is_empty() {
+                    continue;
+                }
+                let line = lines.last_mut().expect("there is always a line");
+                match line.spans.last_mut() {
+                    Some(span) if span.style == style => span.content.to_mut().push_str(part),
+                    _ => line.spans.push(Span::styled(part.to_string(), style)),
+                }
+            }
+        }
+        Some(Text::from(lines))
+    }
+}
+
+/// length in bytes of the string literal at the start of `source`, which starts with `quote`.
+fn string_len(source: &str, quote: &str, escapes: bool) -> usize {
+    let mut chars = source.char_indices().skip(quote.chars().count());
+    while let Some((idx, ch)) = chars.next() {
+        if escapes && ch == '\\' {
+            chars.next();
+        } else if source[idx..].starts_with(quote) {
+            return idx + quote.len();
+        } else if ch == '\n' && quote.len() == 1 {
+            return idx;
+        }
+    }
+    source.len()
+}
+
+/// length in bytes of the rust raw string at the start of `source`, like `r#"..."#`.
+fn raw_string_len(source: &str) -> Option<usize> {
+    let hashes = source[1..].chars().take_while(|&ch| ch == '#').count();
+    if !source[1 + hashes..].starts_with('"') {
+        return None;
+    }
+    let end = format!("\"{}", "#".repeat(hashes));
+    let body = 2 + hashes;
+    Some(
+        source[body..]
+            .find(&end)
+            .map_or(source.len(), |idx| body + idx + end.len()),
+    )
+}
+
+/// length in bytes of the number at the start of `source`, including suffixes like `u8`.
+fn number_len(source: &str) -> usize {
+    let mut len = 0;
+    let mut chars = source.char_indices().peekable();
+    while let Some((idx, ch)) = chars.next() {
+        let fraction = ch == '.' && chars.peek().is_some_and(|&(_, next)| next.is_ascii_digit());
+        if !(ch.is_alphanumeric() || ch == '_' || fraction) {
+            break;
+        }
+        len = idx + ch.len_utf8();
+    }
+    len
+}
+
+fn tokenize(source: &str, language: Language) -> Vec<(TokenKind, &str)> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    let mut line_start = true;
+    let mut assigned = false;
+    while let Some(ch) = rest.chars().next() {
+        let line = &rest[..rest.find('\n').unwrap_or(rest.len())];
+        let in_key = language == Language::Toml && !assigned && line.contains('=');
+        let (kind, len) = match ch {
+            '\n' => (TokenKind::Plain, 1),
+            ' ' | '\t' | '\r' => (TokenKind::Plain, ch.len_utf8()),
+            '/' if language == Language::Rust && rest.starts_with("//") => {
+                (TokenKind::Comment, rest.find('\n').unwrap_or(rest.len()))
+            }
+            '/' if language == Language::Rust && rest.starts_with("/*") => {
+                let end = rest[2..].find("*/").map_or(rest.len(), |idx| idx + 4);
+                (TokenKind::Comment, end)
+            }
+            '#' if language == Language::Toml => {
+                (TokenKind::Comment, rest.find('\n').unwrap_or(rest.len()))
+            }
+            '[' if language == Language::Toml && line_start => {
+                let end = rest.find(['\n', ']']).map_or(rest.len(), |idx| idx + 1);
+                (TokenKind::Key, end)
+            }
+            '"' if language == Language::Toml && rest.starts_with("\"\"\"") => {
+                (TokenKind::String, string_len(rest, "\"\"\"", true))
+            }
+            '\'' if language == Language::Toml && rest.starts_with("'''") => {
+                (TokenKind::String, string_len(rest, "'''", false))
+            }
+            '\'' if language == Language::Toml => (TokenKind::String, string_len(rest, "'", false)),
+            '\'' if language == Language::Rust => {
+                // char literals close within a few characters, lifetimes don't
+                let mut chars = rest.chars().skip(1);
+                let is_char = matches!(
+                    (chars.next(), chars.next()),
+                    (Some('\\'), _) | (Some(_), Some('\''))
+                );
+                if is_char {
+                    (TokenKind::String, string_len(rest, "'", true))
+                } else {
+                    let len = rest[1..]
+                        .find(|ch| !language.is_ident_char(ch))
+                        .map_or(rest.len(), |idx| idx + 1);
+                    (TokenKind::Keyword, len)
+                }
+            }
+            'r' if language == Language::Rust && raw_string_len(rest).is_some() => {
+                (TokenKind::String, raw_string_len(rest).unwrap_or_default())
+            }
+            '"' => {
+                let len = string_len(rest, "\"", true);
+                let is_key = in_key
+                    || language == Language::Json && rest[len..].trim_start().starts_with(':');
+                let kind = if is_key {
+                    TokenKind::Key
+                } else {
+                    TokenKind::String
+                };
+                (kind, len)
+            }
+            '-' | '+'
+                if language != Language::Rust
+                    && rest[1..].starts_with(|ch: char| ch.is_ascii_digit()) =>
+            {
+                (TokenKind::Number, 1 + number_len(&rest[1..]))
+            }
+            '0'..='9' => (TokenKind::Number, number_len(rest)),
+            ch if language.is_ident_char(ch) => {
+                let len = rest
+                    .find(|ch| !language.is_ident_char(ch))
+                    .unwrap_or(rest.len());
+                let kind = language.classify(&rest[..len], &rest[len..], in_key);
+                (kind, len)
+            }
+            ch => (TokenKind::Plain, ch.len_utf8()),
+        };
+        let (token, tail) = rest.split_at(len);
+        tokens.push((kind, token));
+        if token.contains('\n') {
+            line_start = true;
+            assigned = false;
+        } else if !token.trim().is_empty() {
+            line_start = false;
+            assigned |= token == "=";
+        }
+        rest = tail;
+    }
+    tokens
+}
+
+/// highlighted source code with optional line numbers. see the [module docs][self].
+#[derive(Debug, Clone, Default)]
+pub struct CodeBlock {
+    text: Text<'static>,
+    line_numbers: bool,
+    highlighted: Option<RangeInclusive<usize>>,
+    style: Style,
+    gutter_style: Style,
+    highlight_style: Style,
+}
+
+impl CodeBlock {
+    /// highlights `source` as `language` with the [`BuiltinHighlighter`].
+    pub fn new(source: &str, language: &str) -> Self {
+        Self::with_highlighter(source, language, &BuiltinHighlighter::default())
+    }
+
+    /// highlights `source` as `language` with a custom [`Highlighter`]. if it does not support
+    /// the language, the source is shown as plain text.
+    pub fn with_highlighter(source: &str, language: &str, highlighter: &impl Highlighter) -> Self {
+        let text = highlighter
+            .highlight(source, language)
+            .unwrap_or_else(|| Text::from(source.to_string()));
+        Self {
+            text,
+            line_numbers: false,
+            highlighted: None,
+            style: Style::default(),
+            gutter_style: Style::new().add_modifier(Modifier::DIM),
+            highlight_style: Style::new().bg(Color::DarkGray),
+        }
+    }
+
+    /// shows line numbers in a gutter on the left.
+    #[must_use]
+    pub fn line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    /// highlights a range of lines, counting from 1.
+    #[must_use]
+    pub fn highlight_lines(mut self, lines: RangeInclusive<usize>) -> Self {
+        self.highlighted = Some(lines);
+        self
+    }
+
+    /// sets the style of the line number gutter.
+    /// defaults to: `Style::new().add_modifier(Modifier::DIM)`
+    #[must_use]
+    pub fn gutter_style(mut self, style: Style) -> Self {
+        self.gutter_style = style;
+        self
+    }
+
+    /// sets the style patched onto highlighted lines.
+    /// defaults to: `Style::new().bg(Color::DarkGray)`
+    #[must_use]
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight_style = style;
+        self
+    }
+
+    /// the highlighted source. its tabs are expanded once the code block is spawned.
+    pub fn text(&self) -> &Text<'static> {
+        &self.text
+    }
+
+    pub(crate) fn expand_tabs(&mut self, tab_width: TabWidth) {
+        unicode::expand_tabs_in_text(&mut self.text, tab_width);
+    }
+
+    fn gutter_width(&self) -> usize {
+        if self.line_numbers {
+            // digits, then ` │ `
+            self.text.lines.len().max(1).to_string().len() + 3
+        } else {
+            0
+        }
+    }
+
+    /// the width the code block needs, including the gutter.
+    pub fn width(&self) -> u16 {
+        (self.gutter_width() + unicode::text_width(&self.text))
+            .try_into()
+            .unwrap_or(u16::MAX)
+    }
+
+    /// the number of lines of code.
+    pub fn height(&self) -> u16 {
+        self.text.height().try_into().unwrap_or(u16::MAX)
+    }
+}
+
+impl Widget for CodeBlock {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        (&self).render(area, buf);
+    }
+}
+
+impl Widget for &CodeBlock {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        buf.set_style(area, self.style);
+        let gutter = self.gutter_width();
+        for (idx, (row, line)) in area.rows().zip(&self.text.lines).enumerate() {
+            let number = idx + 1;
+            let mut spans = Vec::with_capacity(line.spans.len() + 1);
+            if self.line_numbers {
+                let number = format!("{number:>width$} │ ", width = gutter - 3);
+                spans.push(Span::styled(number, self.gutter_style));
+            }
+            spans.extend(line.spans.iter().cloned());
+            Line::from(spans).style(line.style).render(row, buf);
+            if self
+                .highlighted
+                .as_ref()
+                .is_some_and(|lines| lines.contains(&number))
+            {
+                buf.set_style(row, self.highlight_style);
+            }
+        }
+    }
+}
+
+impl Styled for CodeBlock {
+    type Item = CodeBlock;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style<S: Into<Style>>(mut self, style: S) -> Self::Item {
+        self.style = style.into();
+        self
+    }
+}
@@ -0,0 +1,190 @@
+//! # Color
+//!
+//! color math that works across terminal palettes.
+//!
+//! effects like [`Shadow`][crate::shadow::Shadow] compute new colors from the ones already in the
+//! buffer. named and indexed colors are converted to rgb first, and the result is converted back
+//! to the palette set by the [`ColorDepth`] resource, so 256 color terminals never receive
+//! truecolor escapes.
+//!
+//! # Example
+//!
+//! ```
+//! # use mana_tui_elemental::prelude::*;
+//! # use mana_tui_utils::resource::Resources;
+//! let mut ctx = ElementCtx::new();
+//! ctx.insert_resource(ColorDepth::detect());
+//! ```
+
+use ratatui::style::Color;
+
+/// resource that sets the colors the terminal can show. colors computed by elemental are
+/// converted to the closest one available.
+/// defaults to: `ColorDepth::TrueColor`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ColorDepth {
+    /// 24 bit rgb colors.
+    #[default]
+    TrueColor,
+    /// the xterm 256 color palette.
+    Ansi256,
+    /// the 16 named ansi colors.
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// guesses the color depth from the `COLORTERM` and `TERM` environment variables.
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        let term = std::env::var("TERM").unwrap_or_default();
+        if matches!(colorterm.as_str(), "truecolor" | "24bit") {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Ansi256
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    /// the closest color to `rgb` this depth can show.
+    pub fn convert(self, (r, g, b): (u8, u8, u8)) -> Color {
+        match self {
+            ColorDepth::TrueColor => Color::Rgb(r, g, b),
+            ColorDepth::Ansi256 => Color::Indexed(nearest_256((r, g, b))),
+            ColorDepth::Ansi16 => {
+                let (idx, _) = ANSI
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, ansi)| distance((r, g, b), **ansi))
+                    .expect("the ansi palette is not empty");
+                ANSI_COLORS[idx]
+            }
+        }
+    }
+}
+
+/// the usual rgb values of the 16 ansi colors, in palette order.
+const ANSI: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (128, 0, 0),
+    (0, 128, 0),
+    (128, 128, 0),
+    (0, 0, 128),
+    (128, 0, 128),
+    (0, 128, 128),
+    (192, 192, 192),
+    (128, 128, 128),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (0, 0, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+const ANSI_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// levels of the 6x6x6 color cube in the 256 color palette.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// the rgb value of a color, or `None` for [`Color::Reset`], whose value is up to the terminal.
+pub fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Reset => None,
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        Color::Indexed(idx @ 0..=15) => Some(ANSI[usize::from(idx)]),
+        Color::Indexed(idx @ 16..=231) => {
+            let idx = idx - 16;
+            Some((
+                CUBE[usize::from(idx / 36)],
+                CUBE[usize::from(idx / 6 % 6)],
+                CUBE[usize::from(idx % 6)],
+            ))
+        }
+        Color::Indexed(idx) => {
+            let level = 8 + (idx - 232) * 10;
+            Some((level, level, level))
+        }
+        named => ANSI_COLORS
+            .iter()
+            .position(|&ansi| ansi == named)
+            .map(|idx| ANSI[idx]),
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| u32::from(a.abs_diff(b)).pow(2);
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+/// closest color in the cube and gray ramp of the 256 color palette. the first 16 colors are
+/// skipped since terminals often change them.
+fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |value: u8| {
+        CUBE.iter()
+            .enumerate()
+            .min_by_key(|(_, level)| level.abs_diff(value))
+            .map_or(0, |(idx, _)| idx as u8)
+    };
+    let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = 16 + 36 * r + 6 * g + b;
+    let cube_rgb = (
+        CUBE[usize::from(r)],
+        CUBE[usize::from(g)],
+        CUBE[usize::from(b)],
+    );
+
+    let average = (u16::from(rgb.0) + u16::from(rgb.1) + u16::from(rgb.2)) / 3;
+    let gray = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_level = 8 + gray * 10;
+    if distance(rgb, (gray_level, gray_level, gray_level)) < distance(rgb, cube_rgb) {
+        232 + gray
+    } else {
+        cube
+    }
+}
+
+/// darkens `rgb` by `darken` and moves it towards gray by `desaturate`, both from 0 to 1.
+pub fn shade((r, g, b): (u8, u8, u8), darken: f32, desaturate: f32) -> (u8, u8, u8) {
+    let luma = 0.299 * f32::from(r) + 0.587 * f32::from(g) + 0.114 * f32::from(b);
+    let darken = darken.clamp(0.0, 1.0);
+    let desaturate = desaturate.clamp(0.0, 1.0);
+    let channel = |value: u8| {
+        let value = f32::from(value);
+        let value = value + (luma - value) * desaturate;
+        (value * (1.0 - darken)).round().clamp(0.0, 255.0) as u8
+    };
+    (channel(r), channel(g), channel(b))
+}
+
+/// mixes `from` and `to`, with `t` from 0 (only `from`) to 1 (only `to`).
+pub fn mix(from: (u8, u8, u8), to: (u8, u8, u8), t: f32) -> (u8, u8, u8) {
+    let t = t.clamp(0.0, 1.0);
+    let channel = |from: u8, to: u8| {
+        let (from, to) = (f32::from(from), f32::from(to));
+        (from + (to - from) * t).round().clamp(0.0, 255.0) as u8
+    };
+    (
+        channel(from.0, to.0),
+        channel(from.1, to.1),
+        channel(from.2, to.2),
+    )
+}
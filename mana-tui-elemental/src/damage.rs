@@ -0,0 +1,269 @@
+//! # Damage
+//!
+//! partial rendering of the parts of the tree that changed.
+//!
+//! [`ElementCtx::render_damaged`] compares the tree with the one it rendered last time and only
+//! renders the elements that overlap the areas that changed. an area is damaged when an element
+//! is added, removed, moved, resized or scrolled, or when its widget or style changes. widgets
+//! are compared through a hash of their [`Debug`] output.
+//!
+//! changes that do not show in the widget, like the state of a
+//! [`Stateful`][crate::ui::Stateful] widget, are not detected. mark the element with
+//! [`ElementCtx::damage`] to render it again anyway.
+//!
+//! the undamaged parts of `buf` are left as they are, so it has to hold the previous frame.
+//!
+//! # Example
+//!
+//! ```
+//! # use mana_tui_elemental::prelude::*;
+//! # use ratatui::{buffer::Buffer, layout::Rect};
+//! let mut ctx = ElementCtx::new();
+//! let root = ui(Block::new())
+//!     .with((Direction::Horizontal,))
+//!     .children((ui(Text::raw("12:00")), ui(Text::raw(" dashboard"))));
+//! let root = ctx.spawn_ui(root);
+//!
+//! let mut buf = Buffer::empty(Rect::new(0, 0, 20, 1));
+//! ctx.calculate_layout(root, buf.area).unwrap();
+//! ctx.render_damaged(root, buf.area, &mut buf);
+//!
+//! let clock = ctx.get::<&Children>(root).unwrap()[0];
+//! *ctx.get::<&mut Text>(clock).unwrap() = Text::raw("12:01");
+//!
+//! ctx.calculate_layout(root, buf.area).unwrap();
+//! let damage = ctx.render_damaged(root, buf.area, &mut buf);
+//! assert_eq!(damage, [Rect::new(0, 0, 5, 1)]);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Debug, Write};
+use std::hash::{DefaultHasher, Hasher};
+
+use hecs::Entity;
+use mana_tui_utils::resource::Resources;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Offset, Position, Rect},
+};
+use tui_scrollview::{ScrollView, ScrollViewState};
+
+use crate::animation::Transition;
+use crate::layout::{Children, Element, ElementCtx, Props, is_hidden};
+use crate::shadow::{Backdrop, Shadow};
+
+/// marks an element whose components changed since the last frame in a way its widget does not
+/// show. the next [`ElementCtx::render_damaged`] renders its area again and removes the
+/// component.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Damaged;
+
+/// what an element looked like to the last partial render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Snapshot {
+    /// the laid out area.
+    area: Rect,
+    /// the cells the element draws to, including its shadow.
+    reach: Rect,
+    scroll: Option<Position>,
+    /// the [`fingerprint`] of the widget.
+    widget: Option<u64>,
+}
+
+/// resource with the snapshots of the last partial render.
+#[derive(Debug, Default)]
+struct DamageState(HashMap<Element, Snapshot>);
+
+/// state of one pass over the tree.
+struct DamagePass {
+    screen: Rect,
+    animating: bool,
+    previous: HashMap<Element, Snapshot>,
+    next: HashMap<Element, Snapshot>,
+    /// the cells an element and its children draw to.
+    subtrees: HashMap<Element, Rect>,
+    damage: Vec<Rect>,
+}
+
+impl ElementCtx {
+    /// marks `element` as [`Damaged`], for changes its widget does not show.
+    pub fn damage(&mut self, element: Element) {
+        _ = self.world.insert_one(element, Damaged);
+    }
+
+    /// renders the parts of the tree that changed since the last call and returns the damaged
+    /// areas. nothing is rendered if the tree did not change.
+    ///
+    /// elements that do not reach a damaged area are skipped with their children. the others are
+    /// drawn into a scratch buffer, and only the damaged areas are copied to `buf`, so containers
+    /// that are drawn again do not paint over the skipped elements.
+    ///
+    /// see the [`damage`][crate::damage] module for details.
+    pub fn render_damaged(&mut self, root: Element, area: Rect, buf: &mut Buffer) -> Vec<Rect> {
+        let previous = std::mem::take(
+            &mut self
+                .world
+                .get_or_insert_resource_with::<&mut DamageState>(DamageState::default)
+                .0,
+        );
+        let mut pass = DamagePass {
+            screen: area,
+            animating: self.is_animating(),
+            previous,
+            next: HashMap::new(),
+            subtrees: HashMap::new(),
+            damage: Vec::new(),
+        };
+        self.collect_damage(root, area, None, &mut pass);
+
+        // removed elements damage the area they used to cover
+        let DamagePass {
+            previous,
+            next,
+            subtrees,
+            damage,
+            ..
+        } = pass;
+        let removed = previous
+            .iter()
+            .filter(|(element, _)| !next.contains_key(element))
+            .map(|(_, snapshot)| snapshot.reach);
+        let mut regions: Vec<Rect> = Vec::new();
+        for rect in damage.into_iter().chain(removed) {
+            let rect = rect.intersection(area);
+            if !rect.is_empty() && !regions.iter().any(|region| region.union(rect) == *region) {
+                regions.retain(|region| rect.union(*region) != rect);
+                regions.push(rect);
+            }
+        }
+
+        self.world.get_resource::<&mut DamageState>().unwrap().0 = next;
+
+        if !regions.is_empty() {
+            let skip: HashSet<Element> = subtrees
+                .into_iter()
+                .filter(|(_, reach)| !regions.iter().any(|region| region.intersects(*reach)))
+                .map(|(element, _)| element)
+                .collect();
+            let mut scratch = Buffer::empty(buf.area);
+            self.render_skipping(root, area, &mut scratch, &skip);
+            for region in &regions {
+                for position in region.intersection(buf.area).positions() {
+                    buf[position] = scratch[position].clone();
+                }
+            }
+        }
+
+        let damaged: Vec<Element> = self
+            .world
+            .query_mut::<(Entity, &Damaged)>()
+            .into_iter()
+            .map(|(element, _)| element)
+            .collect();
+        for element in damaged {
+            _ = self.world.remove_one::<Damaged>(element);
+        }
+        regions
+    }
+
+    /// `canvas` is the area of the scroll container the element is in, if any. returns the cells
+    /// the element and its children draw to.
+    fn collect_damage(
+        &self,
+        element: Element,
+        bounds: Rect,
+        canvas: Option<Rect>,
+        pass: &mut DamagePass,
+    ) -> Rect {
+        if is_hidden(&self.world, element) {
+            return Rect::ZERO;
+        }
+        let Ok(props) = self.world.get::<&Props>(element) else {
+            return Rect::ZERO;
+        };
+        let laid_out = Rect::new(
+            props.position.x,
+            props.position.y,
+            props.size.x,
+            props.size.y,
+        );
+        let area = props.split_area(bounds, Offset::ZERO);
+        let widget = (props.fingerprint)(&self.world, element);
+        drop(props);
+
+        // elements in a scroll container can end up anywhere in its viewport
+        let reach = canvas.unwrap_or_else(|| self.reach(element, area, pass.screen));
+        let snapshot = Snapshot {
+            area: laid_out,
+            reach,
+            scroll: self
+                .world
+                .get::<&ScrollViewState>(element)
+                .ok()
+                .map(|state| state.offset()),
+            widget,
+        };
+        let previous = pass.previous.get(&element).copied();
+        let animated = pass.animating && self.world.get::<&Transition>(element).is_ok();
+        #[cfg(feature = "fx")]
+        let animated = animated || self.world.get::<&crate::fx::Fx>(element).is_ok();
+        if animated || previous != Some(snapshot) || self.world.get::<&Damaged>(element).is_ok() {
+            pass.damage.push(reach);
+            pass.damage.extend(previous.map(|previous| previous.reach));
+        }
+        pass.next.insert(element, snapshot);
+
+        let canvas = canvas.or_else(|| {
+            self.world
+                .get::<&ScrollView>(element)
+                .is_ok()
+                .then_some(area)
+        });
+        let children = self
+            .world
+            .get::<&Children>(element)
+            .map(|children| children.clone())
+            .unwrap_or_default();
+        let subtree = children.iter().fold(reach, |subtree, &child| {
+            cover(subtree, self.collect_damage(child, area, canvas, pass))
+        });
+        pass.subtrees.insert(element, subtree);
+        subtree
+    }
+
+    /// the cells an element in `area` draws to, including its [`Shadow`] and [`Backdrop`].
+    fn reach(&self, element: Element, area: Rect, screen: Rect) -> Rect {
+        if self.world.get::<&Backdrop>(element).is_ok() {
+            return screen;
+        }
+        match self.world.get::<&Shadow>(element) {
+            Ok(shadow) => cover(area, area.offset(shadow.offset).intersection(screen)),
+            Err(_) => area,
+        }
+    }
+}
+
+/// a hash of the [`Debug`] output of `value`, which shows everything a widget draws.
+pub(crate) fn fingerprint(value: &impl Debug) -> u64 {
+    struct HashWriter(DefaultHasher);
+
+    impl Write for HashWriter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0.write(s.as_bytes());
+            Ok(())
+        }
+    }
+
+    let mut writer = HashWriter(DefaultHasher::new());
+    _ = write!(writer, "{value:?}");
+    writer.0.finish()
+}
+
+/// the smallest rect that covers both `a` and `b`, ignoring empty ones.
+fn cover(a: Rect, b: Rect) -> Rect {
+    match (a.is_empty(), b.is_empty()) {
+        (_, true) => a,
+        (true, false) => b,
+        (false, false) => a.union(b),
+    }
+}
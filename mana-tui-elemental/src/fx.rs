@@ -0,0 +1,226 @@
+//! # Fx
+//!
+//! [tachyonfx](https://docs.rs/tachyonfx) effects attached to elements.
+//!
+//! an element with an [`Fx`] runs its effect over its area after it and its children render.
+//! effects move forward by the frame delta passed to [`ElementCtx::render_with_delta`]. with
+//! [`ElementCtx::render`], they move forward by how much the [`Time`] resource advanced since the
+//! last frame instead. effects are removed once they are done. wrap an effect with
+//! [`Fx::repeating`] to run it forever.
+//!
+//! like [`Transition`][crate::animation::Transition]s, effects are matched between frames by
+//! their place in the tree, so respawning a view does not restart them. an element that is hidden
+//! and shown again starts its effect over.
+//!
+//! # Example
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use mana_tui_elemental::prelude::*;
+//! # use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+//! use tachyonfx::fx;
+//!
+//! let mut ctx = ElementCtx::new();
+//! let fade_in = Fx::new(fx::fade_from_fg(Color::Black, 300));
+//! let root = ctx.spawn_ui(ui(Block::bordered().title_top("hello")).with((fade_in,)));
+//!
+//! # let mut buf = Buffer::empty(Rect::new(0, 0, 20, 3));
+//! for _ in 0..3 {
+//!     ctx.calculate_layout(root, buf.area).unwrap();
+//!     ctx.render_with_delta(root, buf.area, &mut buf, Duration::from_millis(16));
+//! }
+//! ```
+
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use mana_tui_utils::resource::Resources;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Offset, Rect},
+};
+use tachyonfx::{Effect, fx, fx::RepeatMode};
+
+use crate::animation::Time;
+use crate::layout::{Children, Element, ElementCtx, Props, is_hidden};
+
+/// runs a tachyonfx [`Effect`] over the area of the element every frame, until it is done.
+pub struct Fx(Mutex<Option<Effect>>);
+
+impl Fx {
+    /// create a component that runs `effect` once.
+    pub fn new(effect: Effect) -> Self {
+        Self(Mutex::new(Some(effect)))
+    }
+
+    /// create a component that runs `effect` over and over.
+    pub fn repeating(effect: Effect) -> Self {
+        Self::new(fx::repeat(effect, RepeatMode::Forever))
+    }
+
+    fn take(&mut self) -> Option<Effect> {
+        self.0
+            .get_mut()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take()
+    }
+}
+
+impl std::fmt::Debug for Fx {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Fx").finish_non_exhaustive()
+    }
+}
+
+/// the effect of the element at a path. `None` once it is done, so it does not start over.
+struct RunningFx {
+    owner: Element,
+    effect: Option<Mutex<Effect>>,
+}
+
+/// resource with the effects of the tree, keyed by the child indices that lead to their element.
+#[derive(Default)]
+struct FxState {
+    effects: HashMap<Vec<usize>, RunningFx>,
+    elements: HashMap<Element, Vec<usize>>,
+    time: Option<Duration>,
+    delta: Duration,
+    /// the delta passed to [`ElementCtx::render_with_delta`] for the frame being rendered.
+    next_delta: Option<Duration>,
+}
+
+impl ElementCtx {
+    /// renders the tree like [`ElementCtx::render`], moving effects forward by `delta`, the time
+    /// since the last frame, instead of by how much the [`Time`] resource advanced.
+    pub fn render_with_delta(
+        &mut self,
+        root: Element,
+        area: Rect,
+        buf: &mut Buffer,
+        delta: Duration,
+    ) {
+        self.world
+            .get_or_insert_resource_with::<&mut FxState>(FxState::default)
+            .next_delta = Some(delta);
+        self.render(root, area, buf);
+    }
+
+    /// matches the [`Fx`] components in the tree of `root` with the effects of the last frame.
+    pub(crate) fn prepare_fx(&mut self, root: Element) {
+        let has_fx = self.world.query::<&Fx>().iter().next().is_some();
+        if !has_fx && self.world.get_resource::<&FxState>().is_err() {
+            return;
+        }
+        let now = self.world.get_resource::<&Time>().ok().map(|time| time.0);
+        let mut state = std::mem::take(
+            &mut *self
+                .world
+                .get_or_insert_resource_with::<&mut FxState>(FxState::default),
+        );
+        let mut previous = std::mem::take(&mut state.effects);
+        state.elements.clear();
+        state.delta = match (state.next_delta.take(), state.time, now) {
+            (Some(delta), ..) => delta,
+            (None, Some(last), Some(now)) => now.saturating_sub(last),
+            _ => Duration::ZERO,
+        };
+        state.time = now;
+
+        self.collect_fx(root, &mut Vec::new(), &mut previous, &mut state);
+        *self.world.get_resource::<&mut FxState>().unwrap() = state;
+    }
+
+    fn collect_fx(
+        &self,
+        element: Element,
+        path: &mut Vec<usize>,
+        previous: &mut HashMap<Vec<usize>, RunningFx>,
+        state: &mut FxState,
+    ) {
+        if is_hidden(&self.world, element) {
+            return;
+        }
+        if let Ok(mut fx) = self.world.get::<&mut Fx>(element) {
+            let fresh = fx.take();
+            let running = match previous.remove(path.as_slice()) {
+                // the element was spawned again, keep the effect that is already running
+                Some(running) if fresh.is_none() || running.owner != element => RunningFx {
+                    owner: element,
+                    ..running
+                },
+                _ => RunningFx {
+                    owner: element,
+                    effect: fresh.map(Mutex::new),
+                },
+            };
+            state.effects.insert(path.clone(), running);
+            state.elements.insert(element, path.clone());
+        }
+
+        let Ok(children) = self.world.get::<&Children>(element).map(|c| c.clone()) else {
+            return;
+        };
+        for (idx, &child) in children.iter().enumerate() {
+            path.push(idx);
+            self.collect_fx(child, path, previous, state);
+            path.pop();
+        }
+    }
+
+    /// runs the effect of `element` over its area. the arguments are the ones it was rendered
+    /// with.
+    pub(crate) fn render_fx(
+        &mut self,
+        element: Element,
+        area: Rect,
+        buf: &mut Buffer,
+        offset: Offset,
+    ) {
+        let Ok(area) = self
+            .world
+            .get::<&Props>(element)
+            .map(|props| props.split_area(area, offset))
+        else {
+            return;
+        };
+        let done = {
+            let Ok(mut state) = self.world.get_resource::<&mut FxState>() else {
+                return;
+            };
+            let state = &mut *state;
+            let Some(running) = state
+                .elements
+                .get(&element)
+                .and_then(|path| state.effects.get_mut(path))
+            else {
+                return;
+            };
+            let Some(effect) = running.effect.as_mut() else {
+                return;
+            };
+            let effect = effect
+                .get_mut()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            effect.process(state.delta.into(), buf, area);
+            let done = effect.done();
+            if done {
+                running.effect = None;
+            }
+            done
+        };
+        if done {
+            _ = self.world.remove_one::<Fx>(element);
+        }
+    }
+
+    /// whether an [`Fx`] in the last rendered tree is still running.
+    pub(crate) fn has_running_fx(&self) -> bool {
+        self.world.get_resource::<&FxState>().is_ok_and(|state| {
+            state.elements.values().any(|path| {
+                state
+                    .effects
+                    .get(path)
+                    .is_some_and(|fx| fx.effect.is_some())
+            })
+        })
+    }
+}
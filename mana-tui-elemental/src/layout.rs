@@ -3,6 +3,7 @@
 //! implements the layout algorithm.
 use std::{
     any::TypeId,
+    collections::HashSet,
     ops::{Deref, Div},
     sync::Arc,
 };
@@ -13,13 +14,19 @@ use hecs::{CommandBuffer, Component, ComponentError, Entity, Query, World};
 use mana_tui_utils::Ecs;
 use ratatui::{
     buffer::Buffer,
-    layout::{Direction, Margin, Rect},
+    layout::{Direction, Margin, Position, Rect},
     style::{Style, Styled},
+    symbols::merge::MergeStrategy,
     widgets::{Padding, Widget},
 };
 use ratatui::{layout::Offset, widgets::StatefulWidget};
 pub use tui_scrollview::{ScrollView, ScrollViewState};
 
+use crate::animation::Property;
+use crate::scroll::ScrollCanvas;
+use crate::unicode;
+use crate::virtual_list::VirtualList;
+
 /// trait for rendering elements through a shared reference. this is automatically implemented
 /// for anything that implements [`Widget`], [`Clone`] and [`Component`]
 pub trait ElWidget<M>: std::fmt::Debug + Component {
@@ -51,6 +58,126 @@ where
     }
 }
 
+/// renders the wrapped widget through a shared reference instead of cloning it every frame.
+/// works for any widget where `&W` implements [`Widget`], which includes most ratatui widgets.
+///
+/// the style of the element is applied to its area before the widget renders.
+///
+/// # Example
+///
+/// ```
+/// # use mana_tui_elemental::prelude::*;
+/// let long_text = "lorem ipsum ".repeat(1000);
+/// ui(Paragraph::new(long_text).wrap(Wrap { trim: false }).by_reference());
+/// ```
+#[derive(Debug, Clone, Default, d::Deref, d::DerefMut)]
+pub struct ByRef<W> {
+    /// the wrapped widget.
+    #[deref]
+    #[deref_mut]
+    pub widget: W,
+    /// style applied to the element's area before rendering.
+    pub style: Style,
+}
+
+/// marker for [`ElWidget`] implementation of [`ByRef`].
+pub struct ByRefMarker;
+
+impl<W> ElWidget<ByRefMarker> for ByRef<W>
+where
+    W: std::fmt::Debug + Component,
+    for<'a> &'a W: Widget,
+{
+    fn render_element(&self, area: Rect, buf: &mut Buffer) {
+        buf.set_style(area, self.style);
+        (&self.widget).render(area, buf);
+    }
+
+    fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    fn get_style(&self) -> Style {
+        self.style
+    }
+}
+
+/// extension trait for rendering widgets by reference. see [`ByRef`].
+pub trait ByRefExt: Sized {
+    /// wraps the widget in [`ByRef`].
+    fn by_reference(self) -> ByRef<Self> {
+        ByRef {
+            widget: self,
+            style: Style::default(),
+        }
+    }
+}
+
+impl<W> ByRefExt for W where for<'a> &'a W: Widget {}
+
+/// wraps a widget that does not implement [`Styled`]. the style of the element is applied to its
+/// area before the widget renders.
+///
+/// # Example
+///
+/// ```
+/// # use mana_tui_elemental::prelude::*;
+/// # use ratatui::{buffer::Buffer, layout::Rect};
+/// #[derive(Debug, Clone)]
+/// struct Dot;
+///
+/// impl Widget for Dot {
+///     fn render(self, area: Rect, buf: &mut Buffer) {
+///         buf.set_string(area.x, area.y, "•", Style::default());
+///     }
+/// }
+///
+/// ui(Dot.unstyled());
+/// ```
+#[derive(Debug, Clone, Default, d::Deref, d::DerefMut)]
+pub struct Unstyled<W> {
+    /// the wrapped widget.
+    #[deref]
+    #[deref_mut]
+    pub widget: W,
+    /// style applied to the element's area before rendering.
+    pub style: Style,
+}
+
+/// marker for [`ElWidget`] implementation of [`Unstyled`].
+pub struct UnstyledMarker;
+
+impl<W> ElWidget<UnstyledMarker> for Unstyled<W>
+where
+    W: Widget + Clone + std::fmt::Debug + Component,
+{
+    fn render_element(&self, area: Rect, buf: &mut Buffer) {
+        buf.set_style(area, self.style);
+        self.widget.clone().render(area, buf);
+    }
+
+    fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    fn get_style(&self) -> Style {
+        self.style
+    }
+}
+
+/// extension trait for using widgets without [`Styled`] as elements. see [`Unstyled`].
+pub trait UnstyledExt: Sized {
+    /// wraps the widget in [`Unstyled`].
+    fn unstyled(self) -> Unstyled<Self> {
+        Unstyled {
+            widget: self,
+            style: Style::default(),
+        }
+    }
+}
+
+impl<W: Widget + Clone> UnstyledExt for W {}
+
 /// Context struct that drives the layout engine.
 ///
 /// # Usage
@@ -58,7 +185,8 @@ where
 /// - [`ElementCtx::deref`] derefs to the inner [`hecs::World`]
 /// - [`ElementCtx::spawn_ui`] takes a builder generated by the [`ui`][crate::ui::ui] function and spawns it and all its children
 /// - [`ElementCtx::calculate_layout`] takes the root element and calculates sizes and positions.
-/// - [`ElementCtx::render`] renders the tree to the provided buffer.
+/// - [`ElementCtx::render`] renders the tree to the provided buffer, applying the
+///   [`Theme`][crate::theme::Theme] resource if there is one.
 ///
 /// # Example
 ///
@@ -93,19 +221,24 @@ impl ElementCtx {
     pub fn new() -> Self {
         Self::default()
     }
-    fn calculate_fit_sizes(&self, element: Element) -> Result<(), ComponentError> {
+    /// sizes elements to their content on `axis`, from the leaves up.
+    pub(crate) fn calculate_fit_sizes(
+        &self,
+        element: Element,
+        axis: Direction,
+    ) -> Result<(), ComponentError> {
         let mut query = self
             .world
             .query_one::<(&Width, &Height, &Padding, &Children, &Direction)>(element);
         let (width, height, padding, children, direction) = query.get().unwrap();
+        let children = self.layout_children(element, children);
         let mut props_query = self.world.query_one::<&mut Props>(element);
         let props = props_query.get().unwrap();
 
-        if let Size::Fixed(size) = **width {
-            props.size.x = size;
-        }
-        if let Size::Fixed(size) = **height {
-            props.size.y = size;
+        match (axis, **width, **height) {
+            (Direction::Horizontal, Size::Fixed(size), _) => props.size.x = size,
+            (Direction::Vertical, _, Size::Fixed(size)) => props.size.y = size,
+            _ => {}
         }
         let inner_size = props.inner_size_from_padding(padding);
         let mut space_used = AxisSizes::default();
@@ -114,49 +247,39 @@ impl ElementCtx {
 
         children
             .iter()
-            .try_for_each(|child| -> Result<(), ComponentError> {
-                self.calculate_fit_sizes(child)?;
+            .try_for_each(|&child| -> Result<(), ComponentError> {
+                self.calculate_fit_sizes(child, axis)?;
                 Ok(())
             })?;
 
-        for child in children {
+        for &child in &children {
             let mut child_props = self.world.get::<&mut Props>(child)?;
-            if width.should_clamp() {
+            if axis == Direction::Horizontal && width.should_clamp() {
                 child_props.size.x = child_props.size.x.clamp(0, inner_size.x);
             }
-            if height.should_clamp() {
-                child_props.size.y = child_props.size.y.clamp(0, inner_size.x);
+            if axis == Direction::Vertical && height.should_clamp() {
+                child_props.size.y = child_props.size.y.clamp(0, inner_size.y);
             }
             space_used = space_used.increase(child_props.size, *direction);
         }
 
-        let mut query = self.world.query_one::<(
-            &mut Props,
-            &Width,
-            &Height,
-            &Padding,
-            &Children,
-            &Direction,
-            &Gap,
-        )>(element);
-        let (props, width, height, padding, children, direction, gap) = query.get().unwrap();
+        let mut query = self
+            .world
+            .query_one::<(&mut Props, &Width, &Height, &Padding, &Direction, &Spacing)>(element);
+        let (props, width, height, padding, direction, spacing) = query.get().unwrap();
 
         space_used = space_used.pad(*padding, *direction);
-        space_used.main_axis += children.len().saturating_sub(1) as u16 * **gap;
+        space_used.main_axis = add_cells(space_used.main_axis, spacing.between(children.len()));
         let space_used = space_used.to_u16vec2(*direction);
-        match **width {
-            Size::Fit | Size::Grow => {
-                props.size.x = space_used.x;
-            }
+        match (axis, **width, **height) {
+            (Direction::Horizontal, Size::Fit | Size::Grow, _) => props.size.x = space_used.x,
+            (Direction::Vertical, _, Size::Fit | Size::Grow) => props.size.y = space_used.y,
             _ => {}
         }
-        match **height {
-            Size::Fit | Size::Grow => {
-                props.size.y = space_used.y;
-            }
-            _ => {}
-        }
-        tracing::trace!(target: "mana-tui::fit", ?element, ?props.size);
+        tracing::trace!(target: "mana-tui::fit", ?element, ?axis, ?props.size);
+        drop(query);
+        #[cfg(feature = "markdown")]
+        self.fit_markdown(element, axis);
         Ok(())
     }
     fn sum_space_used(&self, elements: &[Element]) -> U16Vec2 {
@@ -167,18 +290,35 @@ impl ElementCtx {
             .map(|props| props.size)
             .sum::<U16Vec2>()
     }
-    fn calculate_grow_sizes(
+    /// hands the space left in each element to its growing children on `axis`, from the root
+    /// down.
+    pub(crate) fn calculate_grow_sizes(
         &self,
         element: Element,
         is_root: bool,
         area: Rect,
+        axis: Direction,
     ) -> Result<(), ComponentError> {
+        if is_root {
+            let mut query = self
+                .world
+                .query_one::<(&mut Props, &Width, &Height)>(element);
+            let (props, width, height) = query.get().unwrap();
+            // if the root element is set to grow, we want it to take up the entire
+            // screen.
+            match axis {
+                Direction::Horizontal if width.is_grow() => props.size.x = area.width,
+                Direction::Vertical if height.is_grow() => props.size.y = area.height,
+                _ => {}
+            }
+        }
+
         let mut query = self
             .world
-            .query_one::<(&mut Props, &Padding, &Children, &Direction, &Gap)>(element);
-        let (props, &padding, children, &direction, &gap) = query.get().unwrap();
+            .query_one::<(&mut Props, &Padding, &Children, &Direction, &Spacing)>(element);
+        let (props, &padding, children, &direction, &spacing) = query.get().unwrap();
 
-        let children = children.clone();
+        let children = self.layout_children(element, children);
         let inner_size = props.inner_size_from_padding(&padding);
 
         drop(query);
@@ -186,41 +326,36 @@ impl ElementCtx {
         let space_used = self.sum_space_used(&children);
         let remaining_size = inner_size.saturating_sub(space_used);
         let mut remaining_size = axify(remaining_size, direction);
-        remaining_size.main_axis = remaining_size
-            .main_axis
-            .saturating_sub(children.len().saturating_sub(1) as u16 * *gap);
+        remaining_size.main_axis =
+            add_cells(remaining_size.main_axis, -spacing.between(children.len()));
 
-        if is_root {
-            let mut query = self
-                .world
-                .query_one::<(&mut Props, &Width, &Height)>(element);
-            let (props, width, height) = query.get().unwrap();
-            // if the root element is set to grow, we want it to take up the entire
-            // screen.
-            if width.is_grow() {
-                props.size.x = area.width;
-            }
-            if height.is_grow() {
-                props.size.y = area.height;
-            }
+        if axis == direction {
+            self.grow_main_axis(&children, direction, remaining_size.main_axis);
+        } else {
+            children
+                .iter()
+                .try_for_each(|&child| -> Result<(), ComponentError> {
+                    let mut child_query =
+                        self.world.query_one::<(&mut Props, &Width, &Height)>(child);
+                    let (child_props, child_width, child_height) = child_query.get().unwrap();
+                    if !cross_size(direction, *child_width, *child_height).is_grow() {
+                        return Ok(());
+                    }
+                    let mut size = AxisSizes::from_u16vec2(child_props.size, direction);
+                    size.cross_axis = axify(inner_size, direction).cross_axis;
+                    child_props.size = size.to_u16vec2(direction);
+                    Ok(())
+                })?;
         }
 
-        // cross axis
-        children
-            .iter()
-            .try_for_each(|child| -> Result<(), ComponentError> {
-                let mut child_query = self.world.query_one::<(&mut Props, &Width, &Height)>(child);
-                let (child_props, child_width, child_height) = child_query.get().unwrap();
-                if !cross_size(direction, *child_width, *child_height).is_grow() {
-                    return Ok(());
-                }
-                let mut size = AxisSizes::from_u16vec2(child_props.size, direction);
-                size.cross_axis = axify(inner_size, direction).cross_axis;
-                child_props.size = size.to_u16vec2(direction);
-                Ok(())
-            })?;
+        for &child in &children {
+            self.calculate_grow_sizes(child, false, area, axis)?;
+        }
 
-        // main axis
+        Ok(())
+    }
+    /// grows the children of an element along its main axis, sharing `remaining` cells.
+    fn grow_main_axis(&self, children: &[Element], direction: Direction, mut remaining: u16) {
         #[derive(Query, Debug)]
         struct GrowQuery<'a> {
             props: &'a mut Props,
@@ -229,105 +364,73 @@ impl ElementCtx {
         }
         #[derive(d::Debug)]
         struct GrowEntry {
-            is_grow: bool,
             #[debug("({}, {})", self.size.main_axis, self.size.cross_axis)]
             size: AxisSizes,
             entity: Element,
         }
         let mut buffer = children
             .iter()
-            .map(|child| (self.query_one::<GrowQuery>(child), child))
-            .map(|(mut grow_query, entity)| {
+            .map(|&child| (self.query_one::<GrowQuery>(child), child))
+            .filter_map(|(mut grow_query, entity)| {
                 let grow_query = grow_query.get().unwrap();
-                let is_grow = main_size(direction, *grow_query.width, *grow_query.height).is_grow();
-                let size = axify(grow_query.props.size, direction);
-                GrowEntry {
-                    is_grow,
-                    size,
-                    entity,
-                }
+                main_size(direction, *grow_query.width, *grow_query.height)
+                    .is_grow()
+                    .then(|| GrowEntry {
+                        size: axify(grow_query.props.size, direction),
+                        entity,
+                    })
             })
             .collect::<Vec<_>>();
         buffer.sort_by_key(|entry| entry.size.main_axis);
-        let mut remaining = remaining_size.main_axis;
-        while let Some([smallest, rest @ ..]) = buffer.get_mut(..) {
-            let second_smallest = rest
-                .iter()
-                .position(|entry| entry.size.main_axis != smallest.size.main_axis);
-            match second_smallest {
-                None => {
-                    // distribute remaining space evenly
-                    // +1 to include smallest element
-                    let grow_count = rest.iter().filter(|entry| entry.is_grow).count() + 1;
-                    if grow_count == 0 {
-                        break;
-                    }
-                    let growth = remaining as usize / grow_count;
-                    let growth = growth as u16;
-                    let remainder = remaining as usize % grow_count;
-                    let mut remainder = remainder as u16;
-                    for entry in buffer.iter_mut() {
-                        if !entry.is_grow {
-                            continue;
-                        }
-                        match remainder {
-                            0 => {
-                                entry.size.main_axis += growth;
-                            }
-                            _ => {
-                                entry.size.main_axis += growth + 1;
-                                remainder -= 1;
-                            }
-                        }
-                    }
-                    break;
-                }
-                Some(second_smallest) => {
-                    let end = second_smallest;
-                    let target_size = rest[second_smallest].size.main_axis;
-                    remaining = remaining
-                        .saturating_sub(target_size.saturating_sub(smallest.size.main_axis));
-                    for entry in buffer[..=end].iter_mut() {
-                        if entry.is_grow {
-                            entry.size.main_axis = target_size;
-                        }
-                    }
-                    if remaining == 0 {
-                        break;
-                    }
-                }
+        // water-filling: raise the smallest entries to the size of the next smallest until the
+        // remaining space runs out
+        let mut level = buffer.first().map_or(0, |entry| entry.size.main_axis);
+        let mut filled = 0;
+        while filled < buffer.len() {
+            while buffer
+                .get(filled)
+                .is_some_and(|entry| entry.size.main_axis <= level)
+            {
+                filled += 1;
+            }
+            let next = buffer
+                .get(filled)
+                .map_or(u16::MAX, |entry| entry.size.main_axis);
+            let growth = (next - level).min(remaining / filled as u16);
+            level += growth;
+            remaining -= growth * filled as u16;
+            if level < next {
+                break;
             }
         }
+        // the space that does not divide evenly goes to the first entries
+        for (idx, entry) in buffer[..filled].iter_mut().enumerate() {
+            entry.size.main_axis = level + u16::from(idx < remaining as usize);
+        }
 
         for entry in buffer {
             let mut query = self.query_one::<GrowQuery>(entry.entity);
             let query = query.get().unwrap();
             query.props.size = entry.size.to_u16vec2(direction);
         }
-
-        for child in children.iter() {
-            self.calculate_grow_sizes(child, false, area)?;
-        }
-
-        Ok(())
     }
-    fn calculate_positions(&self, root: Element) -> Result<(), ComponentError> {
+    pub(crate) fn calculate_positions(&self, root: Element) -> Result<(), ComponentError> {
         let mut query = self.world.query_one::<(
             &Props,
             &Padding,
             &Children,
             &Direction,
-            &Gap,
+            &Spacing,
             &MainJustify,
             &CrossJustify,
         )>(root);
-        let (&props, &padding, children, &dir, &gap, &main_justify, &cross_justify) =
+        let (&props, &padding, children, &dir, &spacing, &main_justify, &cross_justify) =
             query.get().unwrap();
-        let children = children.clone();
+        let children = self.layout_children(root, children);
         drop(query);
         let space_used = self.sum_space_used(&children);
         let space_used = axify(space_used, dir).main_axis;
-        let space_used = space_used + *gap * children.len().saturating_sub(1) as u16;
+        let space_used = add_cells(space_used, spacing.between(children.len()));
         let inner_size = props.size.saturating_sub(u16vec2(
             padding.left + padding.right,
             padding.top + padding.bottom,
@@ -395,16 +498,14 @@ impl ElementCtx {
             }
             MainJustify::SpaceEvenly if children.is_empty() => AlignValues::default(),
             MainJustify::SpaceEvenly => {
-                let div_by = (children.len() * 2) as u16 + 2;
-                if div_by == 0 {
-                    AlignValues::default()
-                } else {
-                    let space = remaining_size / div_by;
-                    AlignValues {
-                        start: space * 2,
-                        inbetween: space * 2,
-                        remainder: 0,
-                    }
+                // one space before each child and one after the last
+                let div_by = children.len() as u16 + 1;
+                let space = remaining_size / div_by;
+                let space_rem = remaining_size % div_by;
+                AlignValues {
+                    start: space,
+                    inbetween: space,
+                    remainder: space_rem,
                 }
             }
             MainJustify::End => AlignValues {
@@ -416,7 +517,7 @@ impl ElementCtx {
 
         children
             .iter()
-            .try_for_each(|child| -> Result<(), ComponentError> {
+            .try_for_each(|&child| -> Result<(), ComponentError> {
                 {
                     let mut child_props = self.world.get::<&mut Props>(child)?;
                     child_props.position = props.position;
@@ -445,7 +546,8 @@ impl ElementCtx {
                                 inner_size.x.saturating_sub(child_props.size.x);
                         }
                     }
-                    align.start += *gap + align.inbetween + align.tick_rem();
+                    align.start =
+                        add_cells(align.start + align.inbetween + align.tick_rem(), *spacing);
                 }
                 self.calculate_positions(child)?;
                 Ok(())
@@ -460,78 +562,278 @@ impl ElementCtx {
     /// this will error if any element index is invalid. this can only happen if you manually despawn
     /// entities using [`hecs::World::despawn`] or other such methods.
     pub fn calculate_layout(&mut self, element: Element, area: Rect) -> Result<(), ComponentError> {
-        self.calculate_fit_sizes(element)?;
-        self.calculate_grow_sizes(element, true, area)?;
+        // padding presets of the theme change the layout
+        self.apply_theme();
+        self.resolve_spacing();
+        // widths are settled before heights, so text that wraps is measured at the width it gets
+        for axis in [Direction::Horizontal, Direction::Vertical] {
+            self.calculate_fit_sizes(element, axis)?;
+            self.calculate_grow_sizes(element, true, area, axis)?;
+        }
+        // children are positioned in the size their parent shows this frame
+        self.animate(element, Property::Size);
         self.calculate_positions(element)?;
         self.layout_postprocess();
+        self.animate(element, Property::Position);
         Ok(())
     }
-    fn layout_postprocess(&mut self) {
-        for (props, scrollview, padding) in
-            self.query_mut::<(&mut Props, &mut ScrollView, Option<&Padding>)>()
+    pub(crate) fn is_displayed(&self, element: Element) -> bool {
+        self.world
+            .get::<&Display>(element)
+            .map_or(true, |display| *display != Display::None)
+    }
+    pub(crate) fn displayed_children(&self, children: &Children) -> Vec<Element> {
+        children
+            .iter()
+            .filter(|&child| self.is_displayed(child))
+            .collect()
+    }
+    /// children that take part in the layout passes. the rows of a [`VirtualList`] are laid out
+    /// by the list itself.
+    fn layout_children(&self, element: Element, children: &Children) -> Vec<Element> {
+        if self.world.get::<&VirtualList>(element).is_ok() {
+            return Vec::new();
+        }
+        self.displayed_children(children)
+    }
+    /// resolves the [`Spacing`] of every element, so the layout passes do not look up
+    /// [`BorderCollapse`] themselves.
+    fn resolve_spacing(&mut self) {
+        for (gap, collapse, spacing) in self
+            .world
+            .query_mut::<(&Gap, Option<&BorderCollapse>, &mut Spacing)>()
         {
-            let inner_size = props.inner_size_from_padding(padding.unwrap_or(&Padding::ZERO));
-            *scrollview = ScrollView::new(ratatui::layout::Size {
-                width: inner_size.x,
-                height: inner_size.y,
-            });
+            // adjacent children share a cell when their borders collapse
+            let overlap = i32::from(**gap == 0 && collapse.is_some());
+            *spacing = Spacing(i32::from(**gap) - overlap);
+        }
+    }
+    fn layout_postprocess(&mut self) {
+        for (props, display) in self.query_mut::<(&mut Props, &Display)>() {
+            if *display == Display::None {
+                props.size = U16Vec2::ZERO;
+            }
         }
+        self.layout_virtual_lists();
+        self.size_scroll_canvases();
     }
     /// renders the tree.
     ///
     /// also see [`ratatui::prelude::Rect`], [`ratatui::prelude::Buffer`]
     pub fn render(&mut self, root: Element, area: Rect, buf: &mut Buffer) {
+        self.render_skipping(root, area, buf, &HashSet::new());
+    }
+
+    /// renders the tree, leaving out the elements in `skip` and their children.
+    pub(crate) fn render_skipping(
+        &mut self,
+        root: Element,
+        area: Rect,
+        buf: &mut Buffer,
+        skip: &HashSet<Element>,
+    ) {
+        self.apply_changed_theme();
+        self.animate(root, Property::Style);
+        #[cfg(feature = "fx")]
+        self.prepare_fx(root);
         // render self
-        self.render_impl(root, area, buf, Offset { x: 0, y: 0 });
+        self.render_impl(root, area, buf, Viewport::new(area), skip);
+    }
+
+    fn render_impl(
+        &mut self,
+        root: Element,
+        area: Rect,
+        buf: &mut Buffer,
+        viewport: Viewport,
+        skip: &HashSet<Element>,
+    ) {
+        if skip.contains(&root) {
+            return;
+        }
+        self.render_element(root, area, buf, viewport, skip);
+        // effects run over the element and its children
+        #[cfg(feature = "fx")]
+        self.render_fx(root, area, buf, viewport.offset);
+    }
+
+    /// renders a child of a container. when the container has [`BorderCollapse`], the borders the
+    /// child draws on its edges are merged with the ones under them.
+    fn render_child(
+        &mut self,
+        child: Element,
+        area: Rect,
+        buf: &mut Buffer,
+        viewport: Viewport,
+        collapse: Option<MergeStrategy>,
+        skip: &HashSet<Element>,
+    ) {
+        let Some(strategy) = collapse else {
+            self.render_impl(child, area, buf, viewport, skip);
+            return;
+        };
+        let edges = self
+            .world
+            .get::<&Props>(child)
+            .map_or(Rect::ZERO, |props| props.split_area(area, viewport.offset))
+            .intersection(buf.area);
+        let under: Vec<_> = edge_positions(edges).map(|pos| buf[pos].clone()).collect();
+        self.render_impl(child, area, buf, viewport, skip);
+        for (pos, mut merged) in edge_positions(edges).zip(under) {
+            let cell = &mut buf[pos];
+            if *cell == merged {
+                continue;
+            }
+            merged.merge_symbol(cell.symbol(), strategy);
+            cell.set_symbol(merged.symbol());
+        }
     }
 
-    fn render_impl(&mut self, root: Element, area: Rect, buf: &mut Buffer, offset: Offset) {
+    fn render_element(
+        &mut self,
+        root: Element,
+        area: Rect,
+        buf: &mut Buffer,
+        viewport: Viewport,
+        skip: &HashSet<Element>,
+    ) {
+        let Viewport {
+            offset,
+            screen,
+            visible,
+            scrolled,
+        } = viewport;
+        if is_hidden(&self.world, root) {
+            return;
+        }
         let mut query = self
             .world
             .query_one::<(&mut Props, Option<&Children>)>(root);
         let (props, children) = query.get().unwrap();
+        let position = props.position;
         let area = props.split_area(area, offset);
 
-        (props.render)(self, root, area, buf);
+        self.render_shadows(root, area, buf);
+        if !self.render_text_overflow(root, props.render, area, buf) {
+            (props.render)(self, root, area, buf);
+        }
+        self.overlay_animated_style(root, area, buf);
+        unicode::clear_straddling(buf, area);
+
+        let children = children.cloned();
+        drop(query);
+        if scrolled {
+            _ = self.world.insert_one(
+                root,
+                RenderedArea(area.intersection(visible).offset(screen)),
+            );
+        }
 
         // render children
 
         let Some(children) = children else { return };
+        let collapse = self
+            .world
+            .get::<&BorderCollapse>(root)
+            .ok()
+            .map(|collapse| **collapse);
 
-        let children = children.clone();
-        drop(query);
-
-        if let Ok(mut scrollview) = self.remove_one::<ScrollView>(root) {
-            {
-                for child in children.iter() {
-                    self.render_impl(
-                        child,
-                        scrollview.area(),
-                        scrollview.buf_mut(),
-                        Offset {
-                            x: offset.x - i32::from(area.x),
-                            y: offset.y - i32::from(area.y),
-                        },
-                    );
-                }
-            };
-            {
-                let mut scroll_state = self.get::<&mut ScrollViewState>(root);
-                let mut default_scroll_state = ScrollViewState::default();
-                scrollview.clone().render(
-                    area,
-                    buf,
-                    scroll_state
-                        .as_deref_mut()
-                        .unwrap_or(&mut default_scroll_state),
-                );
-            }
-
-            _ = self.insert_one(root, scrollview);
-        } else {
+        let Ok(mut scrollview) = self.world.remove_one::<ScrollView>(root) else {
             for child in children.iter() {
-                self.render_impl(child, area, buf, offset);
+                self.render_child(child, area, buf, viewport, collapse, skip);
             }
+            return;
+        };
+
+        let padding = self
+            .world
+            .get::<&Padding>(root)
+            .map_or(Padding::ZERO, |padding| *padding);
+        let canvas = self
+            .world
+            .get::<&ScrollCanvas>(root)
+            .map(|canvas| *canvas)
+            .unwrap_or_default();
+        let viewport = Rect {
+            x: area.x + padding.left,
+            y: area.y + padding.top,
+            width: area.width.saturating_sub(padding.left + padding.right),
+            height: area.height.saturating_sub(padding.top + padding.bottom),
+        };
+        let scroll_offset = self
+            .world
+            .get::<&ScrollViewState>(root)
+            .map_or(Position::ORIGIN, |state| state.offset());
+        let scroll_offset = Position {
+            x: scroll_offset.x.min(canvas.max_offset.x),
+            y: scroll_offset.y.min(canvas.max_offset.y),
+        };
+
+        // children are laid out relative to the inner area of the container
+        let canvas_offset = Offset {
+            x: -i32::from(position.x + padding.left),
+            y: -i32::from(position.y + padding.top),
+        };
+        let canvas_screen = Offset {
+            x: screen.x + i32::from(viewport.x) - i32::from(scroll_offset.x),
+            y: screen.y + i32::from(viewport.y) - i32::from(scroll_offset.y),
+        };
+        let shown = viewport.intersection(visible);
+        let canvas_visible = Rect {
+            x: shown.x - viewport.x + scroll_offset.x,
+            y: shown.y - viewport.y + scroll_offset.y,
+            width: shown.width.min(canvas.viewport.x),
+            height: shown.height.min(canvas.viewport.y),
+        };
+
+        let canvas_viewport = Viewport {
+            offset: canvas_offset,
+            screen: canvas_screen,
+            visible: canvas_visible,
+            scrolled: true,
+        };
+
+        let canvas_area = scrollview.area();
+        scrollview.buf_mut().reset();
+        for child in children.iter() {
+            let buf = scrollview.buf_mut();
+            self.render_child(child, canvas_area, buf, canvas_viewport, collapse, skip);
+        }
+        {
+            let mut scroll_state = self.world.get::<&mut ScrollViewState>(root);
+            let mut default_scroll_state = ScrollViewState::default();
+            let scroll_state = scroll_state
+                .as_deref_mut()
+                .unwrap_or(&mut default_scroll_state);
+            scroll_state.set_offset(scroll_offset);
+            scrollview.clone().render(viewport, buf, scroll_state);
+        }
+
+        _ = self.world.insert_one(root, scrollview);
+    }
+}
+
+/// where the elements of a render pass end up.
+#[derive(Debug, Clone, Copy)]
+struct Viewport {
+    /// maps layout positions to the buffer.
+    offset: Offset,
+    /// maps the buffer to the terminal.
+    screen: Offset,
+    /// the part of the buffer that ends up on screen.
+    visible: Rect,
+    /// whether the elements are in a scroll container, so not drawn where their layout puts them.
+    scrolled: bool,
+}
+
+impl Viewport {
+    /// draws the elements where their layout puts them, clipped to `area`.
+    const fn new(area: Rect) -> Self {
+        Self {
+            offset: Offset::ZERO,
+            screen: Offset::ZERO,
+            visible: area,
+            scrolled: false,
         }
     }
 }
@@ -648,12 +950,14 @@ pub struct Props {
     pub set_style: fn(&mut World, Element, Style),
     ///
     pub get_style: fn(&World, Element) -> Option<Style>,
+    /// a hash of the widget, used to find the elements that changed. see [`crate::damage`].
+    pub fingerprint: fn(&World, Element) -> Option<u64>,
     ///
     pub typeid: TypeId,
 }
 
 impl Props {
-    fn inner_size_from_padding(&self, padding: &Padding) -> U16Vec2 {
+    pub(crate) fn inner_size_from_padding(&self, padding: &Padding) -> U16Vec2 {
         self.inner_size(Margin {
             horizontal: padding.left + padding.right,
             vertical: padding.top + padding.bottom,
@@ -666,7 +970,7 @@ impl Props {
 }
 
 impl Props {
-    fn split_area(&self, area: Rect, offset: Offset) -> Rect {
+    pub(crate) fn split_area(&self, area: Rect, offset: Offset) -> Rect {
         area.intersection(Rect {
             // DONE: implement position
             x: self.position.x.saturating_add_signed(offset.x as i16),
@@ -709,6 +1013,54 @@ macro_rules! impl_sizing_functions {
 impl_sizing_functions!(Width);
 impl_sizing_functions!(Height);
 
+/// the area an element in a scroll container was last rendered to, in terminal coordinates. this
+/// accounts for clipping and scrolling, unlike [`Props`]. updated by [`ElementCtx::render`].
+///
+/// elements outside of scroll containers are drawn where their [`Props`] put them and don't get
+/// this component.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, d::Deref)]
+pub struct RenderedArea(pub Rect);
+
+/// hides an element and its children. unlike [`Display::None`], hidden elements still take up
+/// space in the layout. they are not rendered and cannot be interacted with.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Hidden;
+
+/// defines whether an element takes part in the layout.
+/// defaults to: `Display::Flex`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Display {
+    /// the element is laid out along with its siblings.
+    #[default]
+    Flex,
+    /// the element and its children are removed from layout, rendering and hit-testing.
+    None,
+}
+
+/// the area `element` was last drawn to, in terminal coordinates. this is its [`RenderedArea`] in
+/// scroll containers, and the area of its [`Props`] everywhere else.
+pub fn rendered_area(world: &World, element: Element) -> Option<Rect> {
+    if let Ok(area) = world.get::<&RenderedArea>(element) {
+        return Some(**area);
+    }
+    let props = world.get::<&Props>(element).ok()?;
+    Some(Rect {
+        x: props.position.x,
+        y: props.position.y,
+        width: props.size.x,
+        height: props.size.y,
+    })
+}
+
+/// whether `element` is [`Hidden`] or has [`Display::None`]. neither is rendered or hit-tested,
+/// and neither are their children.
+pub fn is_hidden(world: &World, element: Element) -> bool {
+    world.get::<&Hidden>(element).is_ok()
+        || world
+            .get::<&Display>(element)
+            .is_ok_and(|display| *display == Display::None)
+}
+
 /// defines the gap on the main axis between child elements.
 /// defaults to: 0`
 ///
@@ -734,6 +1086,68 @@ impl_sizing_functions!(Height);
 #[derive(Debug, Clone, Copy, Default, d::Deref)]
 pub struct Gap(pub u16);
 
+/// collapses the borders of adjacent children into one. children overlap by one cell on the main
+/// axis when the [`Gap`] is 0, and the borders they draw are merged with the ones under them
+/// into junctions using the [`MergeStrategy`]. the widgets of the children are not changed.
+/// defaults to: `BorderCollapse(MergeStrategy::Exact)`
+///
+/// # Example
+///
+/// without `BorderCollapse`
+/// ```plaintext
+/// ┌──┐┌──┐┌──┐
+/// │  ││  ││  │
+/// └──┘└──┘└──┘
+/// ```
+///
+/// with `BorderCollapse`
+/// ```plaintext
+/// ┌──┬──┬──┐
+/// │  │  │  │
+/// └──┴──┴──┘
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, d::Deref)]
+pub struct BorderCollapse(pub MergeStrategy);
+
+impl Default for BorderCollapse {
+    fn default() -> Self {
+        Self(MergeStrategy::Exact)
+    }
+}
+
+/// the cells between adjacent children on the main axis, resolved from the [`Gap`] and
+/// [`BorderCollapse`] of an element at the start of each layout. negative when the borders of the
+/// children overlap. this component is added automatically.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, d::Deref)]
+pub(crate) struct Spacing(i32);
+
+impl Spacing {
+    /// the cells taken by the spacing between `count` children.
+    fn between(self, count: usize) -> i32 {
+        let gaps = i32::try_from(count.saturating_sub(1)).unwrap_or(i32::MAX);
+        self.0.saturating_mul(gaps)
+    }
+}
+
+/// the cells on the edges of `area`.
+fn edge_positions(area: Rect) -> impl Iterator<Item = Position> {
+    area.rows().flat_map(move |row| {
+        // rows between the first and the last only have their ends on the edge
+        let inner = row.y != area.top() && row.y + 1 != area.bottom();
+        let step = if inner {
+            row.width.saturating_sub(1).max(1)
+        } else {
+            1
+        };
+        row.positions().step_by(usize::from(step))
+    })
+}
+
+/// adds a signed number of cells to a length, clamped to the range of `u16`.
+fn add_cells(len: u16, cells: i32) -> u16 {
+    u16::try_from(i32::from(len).saturating_add(cells).max(0)).unwrap_or(u16::MAX)
+}
+
 /// holds a list of entity ids to the element's children. this component is added automatically.
 /// you can use this to iterate the children of an element like this
 ///
@@ -883,13 +1297,13 @@ pub enum MainJustify {
     /// distributes the remaining space evenly between elements such that there is an equal amount of on each side of each item,
     /// accounting for the doubling of space that happens with [`Justify::SpaceAround`]
     ///
-    /// NOTE: this mode usually has poor results on small containers due to rounding issues.
+    /// space that does not divide evenly is added to the gaps between the items, starting with the first.
     ///
     /// ```plaintext
     /// ╭SpaceEvenly───────────╮
-    /// │  ╭──╮  ╭──╮  ╭──╮    │
-    /// │  │#0│  │#1│  │#2│    │
-    /// │  ╰──╯  ╰──╯  ╰──╯    │
+    /// │  ╭──╮   ╭──╮   ╭──╮  │
+    /// │  │#0│   │#1│   │#2│  │
+    /// │  ╰──╯   ╰──╯   ╰──╯  │
     /// ╰──────────────────────╯
     /// ```
     SpaceEvenly,
@@ -946,7 +1360,7 @@ impl Size {
             Size::Grow => false,
         }
     }
-    fn is_grow(&self) -> bool {
+    pub(crate) fn is_grow(&self) -> bool {
         matches!(self, Size::Grow)
     }
 }
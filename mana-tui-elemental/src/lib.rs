@@ -4,15 +4,33 @@
 
 extern crate self as mana_tui_elemental;
 
+pub mod animation;
+pub mod code_block;
+pub mod color;
+pub mod damage;
+#[cfg(feature = "fx")]
+pub mod fx;
 pub mod layout;
+#[cfg(feature = "markdown")]
+pub mod markdown;
 pub mod prelude;
+pub mod scroll;
+pub mod shadow;
+pub mod text_overflow;
+pub mod theme;
 pub mod ui;
+pub mod unicode;
+pub mod virtual_list;
 
 #[cfg(test)]
 mod tests {
     use hecs::World;
     use mana_tui::prelude::*;
-    use ratatui::{buffer::Buffer, layout::Rect, widgets::Wrap};
+    use ratatui::{
+        buffer::Buffer,
+        layout::{Offset, Position, Rect},
+        widgets::Wrap,
+    };
     use strum::IntoEnumIterator;
 
     fn buffer_to_string(buf: &Buffer) -> String {
@@ -230,9 +248,9 @@ mod tests {
             "│ ╰──╯   ╰──╯   ╰──╯   │",
             "╰──────────────────────╯",
             "╭SpaceEvenly───────────╮",
-            "│  ╭──╮  ╭──╮  ╭──╮    │",
-            "│  │00│  │01│  │02│    │",
-            "│  ╰──╯  ╰──╯  ╰──╯    │",
+            "│  ╭──╮   ╭──╮   ╭──╮  │",
+            "│  │00│   │01│   │02│  │",
+            "│  ╰──╯   ╰──╯   ╰──╯  │",
             "╰──────────────────────╯",
             "╭End───────────────────╮",
             "│          ╭──╮╭──╮╭──╮│",
@@ -245,6 +263,1204 @@ mod tests {
         tracing::info!("\ntest_list_justify\n{}", buffer_to_string(&buf));
     }
 
+    #[test]
+    fn test_visibility() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+
+        let mut ctx = ElementCtx::new();
+        let root = ui! {
+            <Block Direction::Horizontal Width::fixed(12) Height::fixed(1)>
+                <Text>"aa"</Text>
+                <Text Hidden>"bb"</Text>
+                <Text Display::None>"cc"</Text>
+                <Text>"dd"</Text>
+            </Block>
+        };
+        let root = ctx.spawn_ui(root);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 12, 1));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        assert_eq!(buf, Buffer::with_lines(["aa  dd      "]));
+
+        let children = ctx.get::<&Children>(root).unwrap().clone();
+        ctx.insert_one(children[1], Display::None).unwrap();
+        ctx.insert_one(children[2], Display::Flex).unwrap();
+        ctx.calculate_layout(root, buf.area).unwrap();
+        let mut buf = Buffer::empty(Rect::new(0, 0, 12, 1));
+        ctx.render(root, buf.area, &mut buf);
+        assert_eq!(buf, Buffer::with_lines(["aaccdd      "]));
+    }
+
+    #[test]
+    fn test_stateful() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+
+        let mut ctx = ElementCtx::new();
+        let root = ui! {
+            <List
+                .items={["one", "two", "three"]}
+                .highlight_symbol=">"
+                .stateful
+                {ListState::default().with_selected(Some(1))}
+                Width::fixed(6)
+                Height::fixed(3)
+            />
+        };
+        let root = ctx.spawn_ui(root);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 3));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        assert_eq!(buf, Buffer::with_lines([" one  ", ">two  ", " three"]));
+
+        ctx.get::<&mut ListState>(root).unwrap().select_next();
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 3));
+        ctx.render(root, buf.area, &mut buf);
+        assert_eq!(buf, Buffer::with_lines([" one  ", " two  ", ">three"]));
+
+        // widgets that render by reference do not have to be `Clone`
+        #[derive(Debug)]
+        struct Counter;
+
+        impl StatefulWidget for &Counter {
+            type State = u16;
+
+            fn render(self, area: Rect, buf: &mut Buffer, state: &mut u16) {
+                *state += 1;
+                buf.set_string(area.x, area.y, state.to_string(), Style::default());
+            }
+        }
+        impl StatefulWidget for Counter {
+            type State = u16;
+
+            fn render(self, area: Rect, buf: &mut Buffer, state: &mut u16) {
+                (&self).render(area, buf, state);
+            }
+        }
+
+        let mut ctx = ElementCtx::new();
+        let root = ctx.spawn_ui(ui(Counter.stateful()).with((Width::fixed(1), Height::fixed(1))));
+        let mut buf = Buffer::empty(Rect::new(0, 0, 1, 1));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        ctx.render(root, buf.area, &mut buf);
+        assert_eq!(*ctx.get::<&u16>(root).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_by_ref_and_unstyled() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+        use mana_tui_utils::resource::Resources;
+
+        #[derive(Debug, Clone)]
+        struct Dots;
+
+        impl Widget for Dots {
+            fn render(self, area: Rect, buf: &mut Buffer) {
+                buf.set_string(area.x, area.y, "..", Style::default());
+            }
+        }
+
+        let mut ctx = ElementCtx::new();
+        ctx.insert_resource(Theme::new().class("dots", Style::new().fg(Color::Red)));
+        let root = ui(Block::new())
+            .with((Direction::Horizontal, Width::fixed(6), Height::fixed(1)))
+            .children((
+                ui(Paragraph::new("abcd").by_reference()).with((Width::fixed(4),)),
+                ui(Dots.unstyled()).with((Width::fixed(2), Height::fixed(1), Class("dots"))),
+            ));
+        let root = ctx.spawn_ui(root);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 1));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+
+        let mut expected = Buffer::with_lines(["abcd.."]);
+        expected.set_style(Rect::new(4, 0, 2, 1), Style::new().fg(Color::Red));
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn test_theme_classes() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+        use mana_tui_utils::resource::Resources;
+
+        struct Active;
+
+        let mut ctx = ElementCtx::new();
+        ctx.insert_resource(
+            Theme::new()
+                .class("button", Style::new().fg(Color::Black).bg(Color::Cyan))
+                .class("button:active", Style::new().bg(Color::Magenta))
+                .class("bold", Style::new().add_modifier(Modifier::BOLD))
+                .state::<Active>("active"),
+        );
+        let root = ui! {
+            <Block Direction::Vertical>
+                <Text Class("button")>"a"</Text>
+                <Text Class("button bold") Active>"b"</Text>
+                <Text .style={Style::new().fg(Color::Red)} Class("button")>"c"</Text>
+            </Block>
+        };
+        let root = ctx.spawn_ui(root);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 1, 3));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+
+        let a = &buf[(0, 0)];
+        assert_eq!((a.fg, a.bg), (Color::Black, Color::Cyan));
+        let b = &buf[(0, 1)];
+        assert_eq!((b.fg, b.bg), (Color::Black, Color::Magenta));
+        assert!(b.modifier.contains(Modifier::BOLD));
+        // inline styles win over classes
+        let c = &buf[(0, 2)];
+        assert_eq!((c.fg, c.bg), (Color::Red, Color::Cyan));
+
+        // swapping the theme restyles without respawning
+        ctx.insert_or_update_resource(
+            Theme::new().class("button", Style::new().fg(Color::White).bg(Color::Blue)),
+        );
+        let mut buf = Buffer::empty(Rect::new(0, 0, 1, 3));
+        ctx.render(root, buf.area, &mut buf);
+        let a = &buf[(0, 0)];
+        assert_eq!((a.fg, a.bg), (Color::White, Color::Blue));
+        let c = &buf[(0, 2)];
+        assert_eq!((c.fg, c.bg), (Color::Red, Color::Blue));
+
+        // changing the inline style after the theme was applied keeps the change
+        let c = ctx.get::<&Children>(root).unwrap()[2];
+        ctx.get::<&mut Text>(c).unwrap().style = Style::new().fg(Color::Green);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 1, 3));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        let c = &buf[(0, 2)];
+        assert_eq!((c.fg, c.bg), (Color::Green, Color::Blue));
+    }
+
+    #[test]
+    fn test_theme_presets() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+        use mana_tui_utils::resource::Resources;
+
+        let mut ctx = ElementCtx::new();
+        ctx.insert_resource(
+            Theme::new()
+                .with_padding("card", Padding::horizontal(1))
+                .with_border_type("card", BorderType::Rounded),
+        );
+        let root = ui! {
+            <Block .borders={Borders::ALL} Class("card") Width::fixed(5) Height::fixed(3)>
+                <Text>"x"</Text>
+            </Block>
+        };
+        let root = ctx.spawn_ui(root);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 5, 3));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+
+        // the preset goes inside the border
+        assert_eq!(
+            *ctx.get::<&Padding>(root).unwrap(),
+            Padding::new(2, 2, 1, 1)
+        );
+        assert_eq!(buf, Buffer::with_lines(["╭───╮", "│ x │", "╰───╯"]));
+
+        // a theme without the padding preset goes back to the border
+        ctx.insert_or_update_resource(Theme::new().with_border_type("card", BorderType::Double));
+        let mut buf = Buffer::empty(Rect::new(0, 0, 5, 3));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        assert_eq!(*ctx.get::<&Padding>(root).unwrap(), Padding::uniform(1));
+        assert_eq!(buf, Buffer::with_lines(["╔═══╗", "║x  ║", "╚═══╝"]));
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn test_theme_toml() {
+        let theme = Theme::from_toml_str(
+            r##"
+            [colors]
+            accent = "#89b4fa"
+
+            [padding]
+            card = [2, 1]
+
+            [borders]
+            card = "quadrant-outside"
+
+            [classes.button]
+            fg = "black"
+            bg = "accent"
+            modifiers = ["bold"]
+            "##,
+        )
+        .unwrap();
+        assert_eq!(
+            theme.get("button"),
+            Some(
+                Style::new()
+                    .fg(Color::Black)
+                    .bg(Color::Rgb(0x89, 0xb4, 0xfa))
+                    .add_modifier(Modifier::BOLD)
+            )
+        );
+        assert_eq!(theme.padding("card"), Some(Padding::symmetric(2, 1)));
+        assert_eq!(theme.border_type("card"), Some(BorderType::QuadrantOutside));
+        assert!(Theme::from_toml_str("[classes.button]\nfg = \"not a color\"").is_err());
+    }
+
+    #[test]
+    fn test_scroll() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+
+        let mut ctx = ElementCtx::new();
+        let root = ui(Block::bordered())
+            .with((
+                Scroll::vertical(),
+                Width::fixed(8),
+                Height::fixed(4),
+                Padding::uniform(1),
+            ))
+            .children((0..10).map(|idx| ui(Text::raw(idx.to_string())).done()));
+        let root = ctx.spawn_ui(root);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 8, 4));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        let children = ctx.get::<&Children>(root).unwrap().clone();
+
+        assert!(ctx.scroll_into_view(children[5]));
+        assert!(!ctx.scroll_into_view(children[4]));
+        assert_eq!(
+            ctx.get::<&ScrollViewState>(root).unwrap().offset(),
+            Position::new(0, 4)
+        );
+
+        ctx.render(root, buf.area, &mut buf);
+        tracing::info!("\ntest_scroll\n{}", buffer_to_string(&buf));
+        assert_eq!(buf[(0, 0)].symbol(), "┌");
+        assert_eq!(buf[(7, 3)].symbol(), "┘");
+        assert_eq!(buf[(1, 1)].symbol(), "4");
+        assert_eq!(buf[(1, 2)].symbol(), "5");
+        assert_eq!(
+            *ctx.get::<&RenderedArea>(children[5]).unwrap(),
+            RenderedArea(Rect::new(1, 2, 1, 1))
+        );
+        assert_eq!(ctx.get::<&RenderedArea>(children[0]).unwrap().area(), 0);
+        // the container is drawn where its layout puts it
+        assert!(ctx.get::<&RenderedArea>(root).is_err());
+
+        assert!(ctx.scroll_at(Position::new(2, 1), Offset { x: 0, y: -10 }));
+        assert!(!ctx.scroll_at(Position::new(2, 1), Offset { x: 0, y: -1 }));
+        assert!(ctx.scroll_by(root, Offset { x: 0, y: 100 }));
+        assert_eq!(
+            ctx.get::<&ScrollViewState>(root).unwrap().offset(),
+            Position::new(0, 8)
+        );
+    }
+
+    #[test]
+    fn test_virtual_list() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+
+        let mut ctx = ElementCtx::new();
+        let root = ui(Block::bordered()).with((
+            VirtualList::new(100_000, |idx| ui(Text::raw(idx.to_string()))),
+            RowStyle(Style::new().fg(Color::Red)),
+            Width::fixed(8),
+            Height::fixed(5),
+        ));
+        let root = ctx.spawn_ui(root);
+        let area = Rect::new(0, 0, 8, 5);
+        ctx.calculate_layout(root, area).unwrap();
+        assert_eq!(ctx.query::<&TuiElMarker>().iter().count(), 4);
+
+        assert!(ctx.scroll_by(root, Offset { x: 0, y: 10 }));
+        ctx.calculate_layout(root, area).unwrap();
+        let before = ctx.get::<&Children>(root).unwrap().clone();
+        assert!(ctx.scroll_by(root, Offset { x: 0, y: 1 }));
+        ctx.calculate_layout(root, area).unwrap();
+        let after = ctx.get::<&Children>(root).unwrap().clone();
+        // rows that stay in view are reused
+        assert_eq!(before[1..], after[..2]);
+        assert_eq!(ctx.query::<&TuiElMarker>().iter().count(), 4);
+
+        let mut buf = Buffer::empty(area);
+        ctx.render(root, area, &mut buf);
+        assert_eq!(buf[(1, 1)].symbol(), "1");
+        assert_eq!(buf[(2, 1)].symbol(), "1");
+        assert_eq!(buf[(2, 3)].symbol(), "3");
+        assert_eq!(buf[(1, 1)].fg, Color::Red);
+
+        assert!(ctx.scroll_to_row(root, 99_999));
+        ctx.calculate_layout(root, area).unwrap();
+        assert!(!ctx.scroll_by(root, Offset { x: 0, y: 1 }));
+        let mut buf = Buffer::empty(area);
+        ctx.render(root, area, &mut buf);
+        tracing::info!("\ntest_virtual_list\n{}", buffer_to_string(&buf));
+        assert_eq!(
+            buf,
+            Buffer::with_lines(["┌──────┐", "│99997 │", "│99998 │", "│99999 │", "└──────┘",])
+        );
+
+        let list = ctx.spawn_ui(
+            ui(Block::new()).with((
+                VirtualList::new(5, |idx| {
+                    ui(Block::new()).with((Height::fixed(idx as u16 + 1),))
+                })
+                .row_height(RowHeight::Measured),
+                Width::fixed(8),
+                Height::fixed(5),
+            )),
+        );
+        ctx.calculate_layout(list, area).unwrap();
+        assert_eq!(ctx.get::<&Children>(list).unwrap().len(), 3);
+        assert!(ctx.scroll_to_row(list, 2));
+        ctx.calculate_layout(list, area).unwrap();
+        let children = ctx.get::<&Children>(list).unwrap().clone();
+        assert_eq!(children.len(), 2);
+        assert_eq!(
+            ctx.get::<&mana_tui::mana_tui_elemental::layout::Props>(children[1])
+                .unwrap()
+                .position
+                .y,
+            2
+        );
+
+        // the gap counts when pulling in rows at the end
+        let list = ctx.spawn_ui(ui(Block::new()).with((
+            VirtualList::new(10, |idx| ui(Text::raw(idx.to_string()))),
+            Gap(1),
+            Width::fixed(8),
+            Height::fixed(5),
+        )));
+        ctx.calculate_layout(list, area).unwrap();
+        assert!(ctx.scroll_to_row(list, 9));
+        ctx.calculate_layout(list, area).unwrap();
+        let mut buf = Buffer::empty(area);
+        ctx.render(list, area, &mut buf);
+        assert_eq!(
+            buffer_to_string(&buf),
+            ["7       ", "        ", "8       ", "        ", "9       "]
+                .map(|line| format!("{line}\n"))
+                .concat()
+        );
+        assert!(!ctx.scroll_by(list, Offset { x: 0, y: 1 }));
+
+        // scrolling stops at the last start the layout keeps, before the end is reached
+        assert!(ctx.scroll_by(list, Offset { x: 0, y: -100 }));
+        ctx.calculate_layout(list, area).unwrap();
+        assert!(ctx.scroll_by(list, Offset { x: 0, y: 100 }));
+        assert_eq!(ctx.get::<&VirtualListState>(list).unwrap().offset(), 7);
+        let list = ctx.spawn_ui(
+            ui(Block::new()).with((
+                VirtualList::new(10, |idx| ui(Text::raw(idx.to_string())))
+                    .row_height(RowHeight::Measured),
+                Width::fixed(8),
+                Height::fixed(5),
+            )),
+        );
+        ctx.calculate_layout(list, area).unwrap();
+        assert!(ctx.scroll_by(list, Offset { x: 0, y: 100 }));
+        assert_eq!(ctx.get::<&VirtualListState>(list).unwrap().offset(), 5);
+        ctx.calculate_layout(list, area).unwrap();
+        assert_eq!(ctx.get::<&VirtualListState>(list).unwrap().offset(), 5);
+
+        // rows are themed before they are measured
+        use mana_tui_utils::resource::Resources;
+        ctx.insert_resource(Theme::new().with_padding("row", Padding::top(1)));
+        let list = ctx.spawn_ui(
+            ui(Block::new()).with((
+                VirtualList::new(10, |idx| {
+                    ui(Text::raw(idx.to_string())).with((Class("row"),))
+                })
+                .row_height(RowHeight::Measured),
+                Width::fixed(8),
+                Height::fixed(5),
+            )),
+        );
+        ctx.calculate_layout(list, area).unwrap();
+        let mut buf = Buffer::empty(area);
+        ctx.render(list, area, &mut buf);
+        assert_eq!(
+            buffer_to_string(&buf),
+            ["        ", "0       ", "        ", "1       ", "        "]
+                .map(|line| format!("{line}\n"))
+                .concat()
+        );
+    }
+
+    #[test]
+    fn test_text_overflow() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+
+        let mut ctx = ElementCtx::new();
+        let root = ui! {
+            <Block Width::fixed(8) Height::fixed(5)>
+                <Text Width::grow()>"clipped text"</Text>
+                <Text TextOverflow::Ellipsis Width::grow()>"ellipsis text"</Text>
+                <Text TextOverflow::EllipsisStart Width::grow()>"ellipsis text"</Text>
+                <Text TextOverflow::EllipsisMiddle Width::grow()>"ellipsis text"</Text>
+                <Text TextOverflow::Ellipsis Width::grow()>"日本語テキスト"</Text>
+            </Block>
+        };
+        let root = ctx.spawn_ui(root);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 8, 5));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        tracing::info!("\ntest_text_overflow\n{}", buffer_to_string(&buf));
+        assert_eq!(
+            buf,
+            Buffer::with_lines(["clipped ", "ellipsi…", "…is text", "elli…ext", "日本語… ",])
+        );
+    }
+
+    #[test]
+    fn test_wide_chars() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+        use mana_tui_utils::resource::Resources;
+
+        /// draws a wide glyph no matter how small its area is.
+        #[derive(Debug, Clone)]
+        struct Wide;
+
+        impl Widget for Wide {
+            fn render(self, area: Rect, buf: &mut Buffer) {
+                buf[(area.x, area.y)].set_symbol("日");
+            }
+        }
+
+        let mut ctx = ElementCtx::new();
+        ctx.insert_resource(TabWidth(2));
+        let root = ui(Block::new())
+            .with((Direction::Horizontal, Width::fixed(16), Height::fixed(1)))
+            .children((
+                ui(Text::raw("日本語")),
+                ui(Text::raw("👨‍👩‍👧")),
+                ui(Text::raw("e\u{301}")),
+                ui(Text::raw("a\tb")),
+                ui(Wide.unstyled()).with((Width::fixed(1), Height::fixed(1))),
+                ui(Text::raw("x")),
+            ));
+        let root = ctx.spawn_ui(root);
+        let children = ctx.get::<&Children>(root).unwrap().clone();
+        let widths: Vec<_> = children
+            .iter()
+            .map(|&child| match **ctx.get::<&Width>(child).unwrap() {
+                Size::Fixed(width) => width,
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(widths, [6, 2, 1, 3, 1, 1]);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 16, 1));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        tracing::info!("\ntest_wide_chars\n{}", buffer_to_string(&buf));
+        assert_eq!(buf, Buffer::with_lines(["日本語👨‍👩‍👧e\u{301}a b x  "]));
+
+        // paragraphs are built from their text after its tabs are expanded
+        let root = ui(Block::new())
+            .with((Width::fixed(16), Height::fixed(2)))
+            .children((
+                ui! { <Paragraph>"a\tb\tc"</Paragraph> },
+                mana_tui::mana_tui_elemental::unicode::expand_tabs("ab\tc", |text| {
+                    Paragraph::new(text).by_reference()
+                }),
+            ));
+        let root = ctx.spawn_ui(root);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 16, 2));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        assert_eq!(
+            buf,
+            Buffer::with_lines(["a b c           ", "ab  c           "])
+        );
+    }
+
+    #[test]
+    fn test_markup() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+        use ratatui::{style::Stylize, text::Line};
+
+        let count = 3;
+        let mut ctx = ElementCtx::new();
+        let root = ui! {
+            <Block Width::fixed(12) Height::fixed(3)>
+                <Text .markup>"[b]bold[/b] [fg=red]{count}[/fg]"</Text>
+                <Line .markup>"[[x] [i]done[/i]"</Line>
+                <Span .markup>"[bg=blue]{count:>3}"</Span>
+            </Block>
+        };
+        let root = ctx.spawn_ui(root);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 12, 3));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        tracing::info!("\ntest_markup\n{}", buffer_to_string(&buf));
+        let mut expected = Buffer::with_lines([
+            Line::from(vec!["bold".bold(), " ".into(), "3".red()]),
+            Line::from(vec!["[x] ".into(), "done".italic()]),
+            Line::from(vec!["  3".on_blue()]),
+        ]);
+        expected.resize(buf.area);
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "markdown")]
+    fn test_markdown() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+        use mana_tui::mana_tui_elemental::layout::Props;
+
+        let mut ctx = ElementCtx::new();
+        let root = ui! {
+            <Block .borders={Borders::ALL} Width::fixed(22)>
+                <Markdown>"# title\n\nsome words that wrap around\n\n- a\n- `b`"</Markdown>
+            </Block>
+        };
+        let root = ctx.spawn_ui(root);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 22, 9));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        tracing::info!("\ntest_markdown\n{}", buffer_to_string(&buf));
+        assert_eq!(ctx.get::<&Props>(root).unwrap().size.y, 9);
+        assert_eq!(
+            buffer_to_string(&buf),
+            [
+                "┌────────────────────┐",
+                "│title               │",
+                "│                    │",
+                "│some words that wrap│",
+                "│around              │",
+                "│                    │",
+                "│• a                 │",
+                "│• b                 │",
+                "└────────────────────┘",
+            ]
+            .map(|line| format!("{line}\n"))
+            .concat()
+        );
+    }
+
+    #[test]
+    fn test_code_block() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+        use mana_tui::mana_tui_elemental::layout::Props;
+        use ratatui::style::Color;
+
+        let mut ctx = ElementCtx::new();
+        let code = CodeBlock::new("let x = 1;\n// done", "rust")
+            .line_numbers(true)
+            .highlight_lines(1..=1);
+        let root = ui! {
+            <Block .borders={Borders::ALL}>
+                { code }
+            </Block>
+        };
+        let root = ctx.spawn_ui(root);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 16, 4));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        tracing::info!("\ntest_code_block\n{}", buffer_to_string(&buf));
+        assert_eq!(ctx.get::<&Props>(root).unwrap().size, glam::u16vec2(16, 4));
+        assert_eq!(
+            buffer_to_string(&buf),
+            [
+                "┌──────────────┐",
+                "│1 │ let x = 1;│",
+                "│2 │ // done   │",
+                "└──────────────┘",
+            ]
+            .map(|line| format!("{line}\n"))
+            .concat()
+        );
+        assert_eq!(buf[(5, 1)].fg, Color::Magenta);
+        assert_eq!(buf[(13, 1)].fg, Color::Cyan);
+        assert_eq!(buf[(5, 1)].bg, Color::DarkGray);
+        assert_eq!(buf[(5, 2)].bg, Color::Reset);
+
+        // tabs are expanded with the tab width of the context
+        use mana_tui_utils::resource::Resources;
+        ctx.insert_resource(TabWidth(2));
+        let root = ctx.spawn_ui(ui(CodeBlock::new("\tx", "rust")));
+        assert!(matches!(**ctx.get::<&Width>(root).unwrap(), Size::Fixed(3)));
+    }
+
+    #[test]
+    fn test_border_collapse() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+
+        let cell = |text: &'static str| {
+            ui(Block::bordered())
+                .with((Width::grow(), Height::fixed(3)))
+                .children((ui(Text::raw(text)),))
+        };
+        let mut ctx = ElementCtx::new();
+        let root = ui(Block::bordered())
+            .with((BorderCollapse::default(), Padding::ZERO, Width::fixed(13)))
+            .children((
+                ui(Block::new())
+                    .with((
+                        Direction::Horizontal,
+                        BorderCollapse::default(),
+                        Width::grow(),
+                    ))
+                    .children((cell("a"), cell("b"), cell("c"))),
+                ui(Block::new())
+                    .with((
+                        Direction::Horizontal,
+                        BorderCollapse::default(),
+                        Width::grow(),
+                    ))
+                    .children((cell("d"), cell("e"))),
+            ));
+        let root = ctx.spawn_ui(root);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 13, 5));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        tracing::info!("\ntest_border_collapse\n{}", buffer_to_string(&buf));
+        assert_eq!(
+            buffer_to_string(&buf),
+            [
+                "┌───┬───┬───┐",
+                "│a  │b  │c  │",
+                "├───┴─┬─┴───┤",
+                "│d    │e    │",
+                "└─────┴─────┘",
+            ]
+            .map(|line| format!("{line}\n"))
+            .concat()
+        );
+        // the widgets of the children are left alone
+        let row = ctx.get::<&Children>(root).unwrap()[0];
+        let cell = ctx.get::<&Children>(row).unwrap()[0];
+        assert_eq!(*ctx.get::<&Block>(cell).unwrap(), Block::bordered());
+    }
+
+    #[test]
+    fn test_shadow() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+        use mana_tui_utils::resource::Resources;
+
+        fn view(effect: impl hecs::DynamicBundle) -> View {
+            ui(Block::new().bg(Color::Rgb(100, 100, 100)))
+                .with((Width::fixed(4), Height::fixed(3)))
+                .children((ui(Block::new())
+                    .with((Width::fixed(2), Height::fixed(1)))
+                    .with(effect),))
+                .into()
+        }
+
+        let mut ctx = ElementCtx::new();
+        let root = ctx.spawn_ui(view((Shadow::default(),)));
+        let mut buf = Buffer::empty(Rect::new(0, 0, 4, 3));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        let bgs: Vec<_> = buf.content().iter().map(|cell| cell.bg).collect();
+        let (lit, dark) = (Color::Rgb(100, 100, 100), Color::Rgb(50, 50, 50));
+        assert_eq!(
+            bgs,
+            [
+                [lit, lit, lit, lit],
+                [lit, dark, dark, lit],
+                [lit, lit, lit, lit]
+            ]
+            .concat()
+        );
+
+        let mut ctx = ElementCtx::new();
+        ctx.insert_resource(ColorDepth::Ansi256);
+        let root = ctx.spawn_ui(view((Backdrop::dim(0.5),)));
+        let mut buf = Buffer::empty(Rect::new(0, 0, 4, 3));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        assert!(
+            buf.content()
+                .iter()
+                .all(|cell| cell.bg == Color::Indexed(236))
+        );
+        assert!(
+            buf.content()
+                .iter()
+                .all(|cell| cell.modifier.contains(Modifier::DIM))
+        );
+
+        let mut ctx = ElementCtx::new();
+        let root = ctx.spawn_ui(ui(Block::new()).with((
+            Width::fixed(2),
+            Height::fixed(1),
+            Backdrop::dim(0.5),
+        )));
+        let mut buf = Buffer::empty(Rect::new(0, 0, 2, 1));
+        ctx.calculate_layout(root, buf.area).unwrap();
+        ctx.render(root, buf.area, &mut buf);
+        assert!(
+            buf.content()
+                .iter()
+                .all(|cell| cell.bg == Color::Rgb(0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_transition() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+        use mana_tui_utils::resource::Resources;
+        use std::time::Duration;
+
+        fn view(width: u16, red: u8) -> View {
+            let transition =
+                Transition::new(Property::All, Duration::from_millis(100)).easing(Easing::Linear);
+            ui(Block::new())
+                .with((Width::fixed(20), Height::fixed(1)))
+                .children((ui(Block::new().bg(Color::Rgb(red, 0, 0))).with((
+                    transition,
+                    Width::fixed(width),
+                    Height::fixed(1),
+                )),))
+                .into()
+        }
+        let mut ctx = ElementCtx::new();
+        ctx.insert_resource(Time::default());
+        let frame = |ctx: &mut ElementCtx, width, red, delta| {
+            ctx.get_resource::<&mut Time>().unwrap().advance(delta);
+            let root = ctx.spawn_ui(view(width, red));
+            let mut buf = Buffer::empty(Rect::new(0, 0, 20, 1));
+            ctx.calculate_layout(root, buf.area).unwrap();
+            ctx.render(root, buf.area, &mut buf);
+            let child = ctx.get::<&Children>(root).unwrap()[0];
+            let area = mana_tui::mana_tui_elemental::layout::rendered_area(ctx, child).unwrap();
+            // the widget keeps the style it is heading to
+            assert_eq!(
+                *ctx.get::<&Block>(child).unwrap(),
+                Block::new().bg(Color::Rgb(red, 0, 0))
+            );
+            ctx.despawn_ui(root);
+            (area.width, buf[(0, 0)].bg)
+        };
+
+        assert_eq!(
+            frame(&mut ctx, 2, 0, Duration::ZERO),
+            (2, Color::Rgb(0, 0, 0))
+        );
+        assert!(!ctx.is_animating());
+        assert_eq!(
+            frame(&mut ctx, 10, 200, Duration::ZERO),
+            (2, Color::Rgb(0, 0, 0))
+        );
+        assert!(ctx.is_animating());
+        assert_eq!(
+            frame(&mut ctx, 10, 200, Duration::from_millis(50)),
+            (6, Color::Rgb(100, 0, 0))
+        );
+        assert_eq!(
+            frame(&mut ctx, 10, 200, Duration::from_millis(50)),
+            (10, Color::Rgb(200, 0, 0))
+        );
+        assert!(!ctx.is_animating());
+
+        // retargeting mid flight to the value shown stops there
+        assert_eq!(
+            frame(&mut ctx, 18, 200, Duration::ZERO),
+            (10, Color::Rgb(200, 0, 0))
+        );
+        assert_eq!(
+            frame(&mut ctx, 18, 200, Duration::from_millis(50)),
+            (14, Color::Rgb(200, 0, 0))
+        );
+        assert_eq!(
+            frame(&mut ctx, 14, 200, Duration::ZERO),
+            (14, Color::Rgb(200, 0, 0))
+        );
+        assert!(!ctx.is_animating());
+        assert_eq!(
+            frame(&mut ctx, 14, 200, Duration::from_millis(50)),
+            (14, Color::Rgb(200, 0, 0))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "fx")]
+    fn test_fx() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+        use mana_tui_utils::resource::Resources;
+        use std::time::Duration;
+        use tachyonfx::fx;
+
+        fn view() -> View {
+            let effect = fx::fade_to_fg(Color::Rgb(200, 0, 0), 100);
+            ui(Block::new())
+                .with((Width::fixed(4), Height::fixed(1)))
+                .children((ui(Text::raw("ab").fg(Color::Rgb(0, 0, 0))).with((Fx::new(effect),)),))
+                .into()
+        }
+        let mut ctx = ElementCtx::new();
+        ctx.insert_resource(Time::default());
+        // the view is spawned again every frame, the effect keeps running
+        let frame = |ctx: &mut ElementCtx, delta| {
+            ctx.get_resource::<&mut Time>().unwrap().advance(delta);
+            let root = ctx.spawn_ui(view());
+            let mut buf = Buffer::empty(Rect::new(0, 0, 4, 1));
+            ctx.calculate_layout(root, buf.area).unwrap();
+            ctx.render(root, buf.area, &mut buf);
+            let child = ctx.get::<&Children>(root).unwrap()[0];
+            let has_fx = ctx.get::<&Fx>(child).is_ok();
+            ctx.despawn_ui(root);
+            (has_fx, buf[(0, 0)].fg)
+        };
+
+        assert!(frame(&mut ctx, Duration::ZERO).0);
+        assert!(ctx.is_animating());
+        assert!(frame(&mut ctx, Duration::from_millis(60)).0);
+        let (has_fx, fg) = frame(&mut ctx, Duration::from_millis(60));
+        assert!(!has_fx);
+        assert_ne!(fg, Color::Rgb(0, 0, 0));
+        assert!(!ctx.is_animating());
+        // finished effects do not start over
+        frame(&mut ctx, Duration::from_millis(60));
+        assert!(!ctx.is_animating());
+
+        // the delta can be passed to render instead of advancing the time
+        let mut ctx = ElementCtx::new();
+        let root = ctx.spawn_ui(view());
+        let mut buf = Buffer::empty(Rect::new(0, 0, 4, 1));
+        let child = ctx.get::<&Children>(root).unwrap()[0];
+        for _ in 0..2 {
+            ctx.calculate_layout(root, buf.area).unwrap();
+            ctx.render_with_delta(root, buf.area, &mut buf, Duration::from_millis(60));
+        }
+        assert!(ctx.get::<&Fx>(child).is_err());
+        assert!(!ctx.is_animating());
+    }
+
+    #[test]
+    fn test_damage() {
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+
+        let mut ctx = ElementCtx::new();
+        let root = ui(Block::new())
+            .with((Width::fixed(6), Height::fixed(3)))
+            .children((ui(Text::raw("a")), ui(Text::raw("b")), ui(Text::raw("c"))));
+        let root = ctx.spawn_ui(root);
+        let children = ctx.get::<&Children>(root).unwrap().clone();
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 3));
+
+        ctx.calculate_layout(root, buf.area).unwrap();
+        assert_eq!(
+            ctx.render_damaged(root, buf.area, &mut buf),
+            [Rect::new(0, 0, 6, 3)]
+        );
+        ctx.calculate_layout(root, buf.area).unwrap();
+        assert!(ctx.render_damaged(root, buf.area, &mut buf).is_empty());
+
+        // cells outside of the damage are left alone
+        buf[(5, 0)].set_symbol("!");
+        *ctx.get::<&mut Text>(children[1]).unwrap() = Text::raw("x");
+        ctx.calculate_layout(root, buf.area).unwrap();
+        assert_eq!(
+            ctx.render_damaged(root, buf.area, &mut buf),
+            [Rect::new(0, 1, 1, 1)]
+        );
+        assert_eq!(buf, Buffer::with_lines(["a    !", "x     ", "c     "]));
+
+        // style changes are found too, and anything else can be marked
+        ctx.get::<&mut Text>(children[0]).unwrap().style = Style::new().red();
+        ctx.damage(children[2]);
+        ctx.calculate_layout(root, buf.area).unwrap();
+        assert_eq!(
+            ctx.render_damaged(root, buf.area, &mut buf),
+            [Rect::new(0, 0, 1, 1), Rect::new(0, 2, 1, 1)]
+        );
+        assert_eq!(buf[(0, 0)].fg, Color::Red);
+        assert!(ctx.get::<&Damaged>(children[2]).is_err());
+
+        ctx.insert_one(children[2], Display::None).unwrap();
+        ctx.calculate_layout(root, buf.area).unwrap();
+        assert_eq!(
+            ctx.render_damaged(root, buf.area, &mut buf),
+            [Rect::new(0, 2, 1, 1)]
+        );
+        tracing::info!("\ntest_damage\n{}", buffer_to_string(&buf));
+        assert_eq!(buf, Buffer::with_lines(["a    !", "x     ", "      "]));
+    }
+
+    #[test]
+    fn test_snapshot() {
+        use mana_tui::testing;
+
+        _ = tracing_subscriber::fmt::try_init();
+        _ = color_eyre::install();
+
+        let view = || {
+            ui(Block::bordered().title_top("hi".bold()))
+                .with((Width::fixed(6), Height::fixed(3)))
+                .children((ui(Text::raw("a").red()),))
+        };
+        let snapshot = testing::styled_snapshot(view(), 6, 3);
+        tracing::info!("\ntest_snapshot\n{snapshot}");
+        assert_eq!(
+            snapshot,
+            [
+                "┌hi──┐",
+                "│a   │",
+                "└────┘",
+                "--- styles ---",
+                "0:1..3 +BOLD",
+                "1:1..2 fg=Red",
+            ]
+            .map(|line| format!("{line}\n"))
+            .concat()
+        );
+        assert_eq!(
+            testing::snapshot(view(), 6, 3),
+            testing::text(&testing::render(view(), 6, 3))
+        );
+        assert_eq!(testing::diff("a\nb\nc", "a\nc\nd"), "  a\n- b\n  c\n+ d\n");
+    }
+
+    mod layout_invariants {
+        use glam::{U16Vec2, u16vec2};
+        use mana_tui::mana_tui_elemental::layout::Props;
+        use mana_tui::prelude::*;
+        use proptest::prelude::*;
+        use ratatui::layout::Rect;
+        use strum::IntoEnumIterator;
+
+        #[derive(Debug, Clone)]
+        struct Node {
+            width: Size,
+            height: Size,
+            direction: Direction,
+            gap: u16,
+            padding: Padding,
+            main_justify: MainJustify,
+            cross_justify: CrossJustify,
+            children: Vec<Node>,
+        }
+
+        impl Node {
+            fn view(&self) -> View {
+                ui(Block::new())
+                    .with((
+                        Width(self.width),
+                        Height(self.height),
+                        self.direction,
+                        Gap(self.gap),
+                        self.padding,
+                        self.main_justify,
+                        self.cross_justify,
+                    ))
+                    .children(self.children.iter().map(Node::view).collect::<Vec<_>>())
+                    .into()
+            }
+
+            /// the same tree with every grow size replaced by fit. its layout has the sizes the
+            /// elements have before they grow.
+            fn without_grow(&self) -> Node {
+                let fit = |size| match size {
+                    Size::Grow => Size::Fit,
+                    size => size,
+                };
+                Node {
+                    width: fit(self.width),
+                    height: fit(self.height),
+                    children: self.children.iter().map(Node::without_grow).collect(),
+                    ..self.clone()
+                }
+            }
+
+            fn main_size(&self) -> Size {
+                match self.direction {
+                    Direction::Horizontal => self.width,
+                    Direction::Vertical => self.height,
+                }
+            }
+        }
+
+        fn size() -> impl Strategy<Value = Size> {
+            prop_oneof![
+                (0..12u16).prop_map(Size::Fixed),
+                Just(Size::Fit),
+                Just(Size::Grow),
+            ]
+        }
+
+        fn node(children: impl Strategy<Value = Vec<Node>>) -> impl Strategy<Value = Node> {
+            (
+                (size(), size()),
+                prop_oneof![Just(Direction::Horizontal), Just(Direction::Vertical)],
+                0..3u16,
+                [0..3u16, 0..3u16, 0..3u16, 0..3u16],
+                prop::sample::select(MainJustify::iter().collect::<Vec<_>>()),
+                prop::sample::select(CrossJustify::iter().collect::<Vec<_>>()),
+                children,
+            )
+                .prop_map(
+                    |(
+                        (width, height),
+                        direction,
+                        gap,
+                        [left, right, top, bottom],
+                        main_justify,
+                        cross_justify,
+                        children,
+                    )| Node {
+                        width,
+                        height,
+                        direction,
+                        gap,
+                        padding: Padding::new(left, right, top, bottom),
+                        main_justify,
+                        cross_justify,
+                        children,
+                    },
+                )
+        }
+
+        fn tree() -> impl Strategy<Value = Node> {
+            node(Just(Vec::new()))
+                .prop_recursive(4, 48, 4, |inner| node(prop::collection::vec(inner, 0..5)))
+        }
+
+        /// `(position, size)` of the children of `element`.
+        fn child_rects(ctx: &ElementCtx, element: Element) -> Vec<(U16Vec2, U16Vec2)> {
+            let children = ctx.get::<&Children>(element).unwrap().clone();
+            children
+                .iter()
+                .map(|&child| {
+                    let props = ctx.get::<&Props>(child).unwrap();
+                    (props.position, props.size)
+                })
+                .collect()
+        }
+
+        /// checks the children of `element` and everything below them. `fit_element` is the same
+        /// element in the layout of [`Node::without_grow`].
+        fn check(
+            node: &Node,
+            ctx: &ElementCtx,
+            element: Element,
+            fit_ctx: &ElementCtx,
+            fit_element: Element,
+        ) -> Result<(), TestCaseError> {
+            let (main, cross): (fn(U16Vec2) -> u16, fn(U16Vec2) -> u16) = match node.direction {
+                Direction::Horizontal => (|v: U16Vec2| v.x, |v: U16Vec2| v.y),
+                Direction::Vertical => (|v: U16Vec2| v.y, |v: U16Vec2| v.x),
+            };
+            let props = *ctx.get::<&Props>(element).unwrap();
+            let padding = node.padding;
+            let inner_position = props.position + u16vec2(padding.left, padding.top);
+            let inner_size = props.size.saturating_sub(u16vec2(
+                padding.left + padding.right,
+                padding.top + padding.bottom,
+            ));
+            let laid_out = child_rects(ctx, element);
+            let fit = child_rects(fit_ctx, fit_element);
+            let gaps = node.gap * node.children.len().saturating_sub(1) as u16;
+            let used = laid_out.iter().map(|&(_, size)| main(size)).sum::<u16>() + gaps;
+            let fit_used = fit.iter().map(|&(_, size)| main(size)).sum::<u16>() + gaps;
+
+            // siblings don't overlap on the main axis
+            for pair in laid_out.windows(2) {
+                let [(position, size), (next, _)] = pair else {
+                    unreachable!()
+                };
+                prop_assert!(main(*position) + main(*size) + node.gap <= main(*next));
+            }
+
+            // children stay inside the inner rect when there is room for them
+            let fits = used <= main(inner_size)
+                && laid_out
+                    .iter()
+                    .all(|&(_, size)| cross(size) <= cross(inner_size));
+            if fits {
+                for &(position, size) in &laid_out {
+                    prop_assert!(position.cmpge(inner_position).all());
+                    prop_assert!((position + size).cmple(inner_position + inner_size).all());
+                }
+            }
+
+            // grow children fill the leftover space exactly, smallest first
+            let grows: Vec<_> = node
+                .children
+                .iter()
+                .zip(laid_out.iter().zip(&fit))
+                .filter(|(child, _)| matches!(child.main_size(), Size::Grow))
+                .map(|(_, (&(_, size), &(_, fit_size)))| (main(size), main(fit_size)))
+                .collect();
+            if !grows.is_empty() && fit_used <= main(inner_size) {
+                prop_assert_eq!(used, main(inner_size));
+                let smallest = grows.iter().map(|&(size, _)| size).min().unwrap();
+                for &(size, fit_size) in &grows {
+                    prop_assert!(size == fit_size || size <= smallest + 1);
+                }
+            }
+
+            // centered children have the same space on both sides
+            if fits
+                && let (Some(&(first, _)), Some(&(last, last_size))) =
+                    (laid_out.first(), laid_out.last())
+                && matches!(
+                    node.main_justify,
+                    MainJustify::Center | MainJustify::SpaceEvenly
+                )
+            {
+                let before = main(first) - main(inner_position);
+                let after = main(inner_position + inner_size) - main(last + last_size);
+                prop_assert!(
+                    before.abs_diff(after) <= 1,
+                    "{before} before, {after} after"
+                );
+            }
+
+            let children = ctx.get::<&Children>(element).unwrap().clone();
+            let fit_children = fit_ctx.get::<&Children>(fit_element).unwrap().clone();
+            for ((child, &element), &fit_element) in node
+                .children
+                .iter()
+                .zip(children.iter())
+                .zip(fit_children.iter())
+            {
+                check(child, ctx, element, fit_ctx, fit_element)?;
+            }
+            Ok(())
+        }
+
+        proptest! {
+            #[test]
+            fn test_layout_invariants(tree in tree(), width in 0..60u16, height in 0..60u16) {
+                let area = Rect::new(0, 0, width, height);
+                let mut ctx = ElementCtx::new();
+                let root = ctx.spawn_ui(tree.view());
+                ctx.calculate_layout(root, area).unwrap();
+                let mut fit_ctx = ElementCtx::new();
+                let fit_root = fit_ctx.spawn_ui(tree.without_grow().view());
+                fit_ctx.calculate_layout(fit_root, area).unwrap();
+
+                let size = ctx.get::<&Props>(root).unwrap().size;
+                if matches!(tree.width, Size::Grow) {
+                    prop_assert_eq!(size.x, width);
+                }
+                if matches!(tree.height, Size::Grow) {
+                    prop_assert_eq!(size.y, height);
+                }
+                check(&tree, &ctx, root, &fit_ctx, fit_root)?;
+            }
+        }
+    }
+
     #[test]
     #[should_panic]
     fn test_hecs() {
@@ -0,0 +1,586 @@
+//! # Markdown
+//!
+//! rendering of CommonMark documents. requires the `markdown` feature.
+//!
+//! [`Markdown`] parses its source once, when it is created, and wraps it to the width of its
+//! element during layout. the wrapped rows are kept until the width changes. by default it grows
+//! on the x axis and fits its wrapped height on the y axis, so it can be placed in scroll
+//! containers and [`Size::Fit`] parents.
+//!
+//! headings, emphasis, inline code, lists, block quotes, code blocks and rules are supported.
+//! links are shown as underlined text without their destination.
+//!
+//! # Example
+//!
+//! ```
+//! # use mana_tui_elemental::prelude::*;
+//! # use mana_tui_elemental::layout::Props;
+//! # use ratatui::{buffer::Buffer, layout::Rect};
+//! let mut ctx = ElementCtx::new();
+//! let root = ui(Markdown::new("# help\n\npress `q` to quit")).with((Width::fixed(12),));
+//! let root = ctx.spawn_ui(root);
+//!
+//! # let mut buf = Buffer::empty(Rect::new(0, 0, 12, 4));
+//! ctx.calculate_layout(root, buf.area).unwrap();
+//! ctx.render(root, buf.area, &mut buf);
+//! // help
+//! //
+//! // press q to
+//! // quit
+//! assert_eq!(ctx.get::<&Props>(root).unwrap().size.y, 4);
+//! ```
+
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Direction, Rect},
+    style::{Color, Modifier, Style, Styled},
+    text::{Line, Span},
+    widgets::{Padding, Widget},
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::layout::{ElWidget, Element, ElementCtx, Height, Props, Size, Width};
+use crate::unicode;
+
+/// a CommonMark document rendered as an element. see the [module docs][self].
+#[derive(Debug, Clone, Default)]
+pub struct Markdown {
+    lines: Vec<MarkdownLine>,
+    style: Style,
+    /// rows from the last wrap, with the width they were wrapped to.
+    wrapped: Option<(u16, Vec<Line<'static>>)>,
+}
+
+/// styles used by [`Markdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkdownStyles {
+    /// style of headings, from `#` to `######`.
+    pub headings: [Style; 6],
+    /// style of `inline code`.
+    pub code: Style,
+    /// style of fenced and indented code blocks. the background fills the width of the element.
+    pub code_block: Style,
+    /// style of link text.
+    pub link: Style,
+    /// style of block quotes, including the bar in front of them.
+    pub quote: Style,
+    /// style of list bullets and numbers.
+    pub list_marker: Style,
+    /// style of horizontal rules.
+    pub rule: Style,
+}
+
+impl Default for MarkdownStyles {
+    fn default() -> Self {
+        let heading = Style::new().add_modifier(Modifier::BOLD);
+        Self {
+            headings: [
+                heading.add_modifier(Modifier::UNDERLINED),
+                heading,
+                heading,
+                heading.add_modifier(Modifier::ITALIC),
+                heading.add_modifier(Modifier::ITALIC),
+                heading.add_modifier(Modifier::ITALIC),
+            ],
+            code: Style::new().fg(Color::Yellow),
+            code_block: Style::new().bg(Color::DarkGray),
+            link: Style::new().add_modifier(Modifier::UNDERLINED),
+            quote: Style::new().add_modifier(Modifier::ITALIC),
+            list_marker: Style::new(),
+            rule: Style::new().add_modifier(Modifier::DIM),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    /// prose, wrapped at word boundaries.
+    Wrap,
+    /// a line of a code block, never wrapped.
+    Code(Style),
+    /// a horizontal rule across the element.
+    Rule(Style),
+}
+
+/// a line of the source, before wrapping.
+#[derive(Debug, Clone)]
+struct MarkdownLine {
+    /// shown before the first row, like a list bullet.
+    prefix: Vec<Span<'static>>,
+    /// shown before the rows the line wraps onto.
+    indent: Vec<Span<'static>>,
+    spans: Vec<Span<'static>>,
+    kind: LineKind,
+}
+
+impl Markdown {
+    /// parses `source` with the default [`MarkdownStyles`].
+    pub fn new(source: &str) -> Self {
+        Self::with_styles(source, &MarkdownStyles::default())
+    }
+
+    /// parses `source` with custom styles.
+    pub fn with_styles(source: &str, styles: &MarkdownStyles) -> Self {
+        let mut builder = Builder::new(styles);
+        let options = Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+        for event in Parser::new_ext(source, options) {
+            builder.event(event);
+        }
+        builder.flush();
+        Self {
+            lines: builder.lines,
+            style: Style::default(),
+            wrapped: None,
+        }
+    }
+
+    /// the width the document needs to render without wrapping.
+    pub fn width(&self) -> u16 {
+        self.lines
+            .iter()
+            .map(|line| {
+                let content = spans_width(&line.spans);
+                match line.kind {
+                    LineKind::Wrap => spans_width(&line.prefix) + content,
+                    LineKind::Code(_) => spans_width(&line.prefix) + content + 2,
+                    LineKind::Rule(_) => spans_width(&line.prefix) + 1,
+                }
+            })
+            .max()
+            .unwrap_or_default()
+            .try_into()
+            .unwrap_or(u16::MAX)
+    }
+
+    /// the number of rows the document takes up when wrapped to `width`.
+    pub fn height(&self, width: u16) -> u16 {
+        self.wrap(width).len().try_into().unwrap_or(u16::MAX)
+    }
+
+    /// the rows of the document wrapped to `width`.
+    pub fn wrap(&self, width: u16) -> Vec<Line<'static>> {
+        let width = usize::from(width);
+        let mut rows = Vec::new();
+        for line in &self.lines {
+            let prefix_width = spans_width(&line.prefix).max(spans_width(&line.indent));
+            match line.kind {
+                LineKind::Wrap => {
+                    let wrapped = wrap_spans(&line.spans, width.saturating_sub(prefix_width));
+                    for (idx, row) in wrapped.into_iter().enumerate() {
+                        let prefix = if idx == 0 { &line.prefix } else { &line.indent };
+                        rows.push(Line::from([prefix.clone(), row].concat()));
+                    }
+                }
+                LineKind::Code(style) => {
+                    let content = spans_width(&line.spans) + 1;
+                    let fill = width.saturating_sub(prefix_width + content);
+                    let mut spans = line.prefix.clone();
+                    spans.push(Span::styled(" ", style));
+                    spans.extend(line.spans.iter().cloned());
+                    spans.push(Span::styled(" ".repeat(fill), style));
+                    rows.push(Line::from(spans));
+                }
+                LineKind::Rule(style) => {
+                    let mut spans = line.prefix.clone();
+                    let rule = "─".repeat(width.saturating_sub(prefix_width));
+                    spans.push(Span::styled(rule, style));
+                    rows.push(Line::from(spans));
+                }
+            }
+        }
+        rows
+    }
+
+    /// wraps the document to `width` unless it already is, and returns the number of rows.
+    fn rewrap(&mut self, width: u16) -> usize {
+        if self
+            .wrapped
+            .as_ref()
+            .is_none_or(|(wrapped, _)| *wrapped != width)
+        {
+            self.wrapped = Some((width, self.wrap(width)));
+        }
+        self.wrapped.as_ref().map_or(0, |(_, rows)| rows.len())
+    }
+}
+
+impl Widget for &Markdown {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        buf.set_style(area, self.style);
+        let rows = match &self.wrapped {
+            Some((width, rows)) if *width == area.width => rows,
+            _ => &self.wrap(area.width),
+        };
+        for (row, line) in area.rows().zip(rows) {
+            line.render(row, buf);
+        }
+    }
+}
+
+/// marker for the [`ElWidget`] implementation of [`Markdown`].
+pub struct MarkdownMarker;
+
+/// renders the rows wrapped during layout, without cloning the document.
+impl ElWidget<MarkdownMarker> for Markdown {
+    fn render_element(&self, area: Rect, buf: &mut Buffer) {
+        Widget::render(self, area, buf);
+    }
+
+    fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    fn get_style(&self) -> Style {
+        self.style
+    }
+}
+
+impl Styled for Markdown {
+    type Item = Markdown;
+
+    fn style(&self) -> Style {
+        self.style
+    }
+
+    fn set_style<S: Into<Style>>(mut self, style: S) -> Self::Item {
+        self.style = style.into();
+        self
+    }
+}
+
+fn spans_width(spans: &[Span]) -> usize {
+    spans
+        .iter()
+        .map(|span| unicode::display_width(&span.content))
+        .sum()
+}
+
+/// wraps `spans` at word boundaries. words longer than `width` are broken between graphemes.
+fn wrap_spans(spans: &[Span<'static>], width: usize) -> Vec<Vec<Span<'static>>> {
+    let width = width.max(1);
+    // every word keeps the style of the space in front of it
+    let mut words: Vec<(Style, Vec<(&str, Style)>)> = vec![(Style::default(), Vec::new())];
+    for span in spans {
+        for grapheme in span.content.graphemes(true) {
+            let word = words.last_mut().expect("there is always a word");
+            if !grapheme.chars().all(char::is_whitespace) {
+                word.1.push((grapheme, span.style));
+            } else if word.1.is_empty() {
+                word.0 = span.style;
+            } else {
+                words.push((span.style, Vec::new()));
+            }
+        }
+    }
+
+    let mut rows: Vec<Vec<(&str, Style)>> = vec![Vec::new()];
+    let mut used = 0;
+    for (space, word) in words.into_iter().filter(|(_, word)| !word.is_empty()) {
+        let word_width: usize = word.iter().map(|(g, _)| unicode::display_width(g)).sum();
+        if used > 0 && used + 1 + word_width > width {
+            rows.push(Vec::new());
+            used = 0;
+        } else if used > 0 {
+            rows.last_mut()
+                .expect("there is always a row")
+                .push((" ", space));
+            used += 1;
+        }
+        for (grapheme, style) in word {
+            let grapheme_width = unicode::display_width(grapheme);
+            if used > 0 && used + grapheme_width > width {
+                rows.push(Vec::new());
+                used = 0;
+            }
+            rows.last_mut()
+                .expect("there is always a row")
+                .push((grapheme, style));
+            used += grapheme_width;
+        }
+    }
+
+    rows.into_iter()
+        .map(|row| {
+            let mut spans: Vec<Span<'static>> = Vec::new();
+            for (grapheme, style) in row {
+                match spans.last_mut() {
+                    Some(span) if span.style == style => span.content.to_mut().push_str(grapheme),
+                    _ => spans.push(Span::styled(grapheme.to_string(), style)),
+                }
+            }
+            spans
+        })
+        .collect()
+}
+
+enum Container {
+    Quote,
+    Item { marker: String, first: bool },
+}
+
+/// turns parser events into lines.
+struct Builder<'s> {
+    styles: &'s MarkdownStyles,
+    lines: Vec<MarkdownLine>,
+    spans: Vec<Span<'static>>,
+    styles_stack: Vec<Style>,
+    containers: Vec<Container>,
+    lists: Vec<Option<u64>>,
+    code_block: bool,
+    /// whether an empty line goes before the next block.
+    separate: bool,
+}
+
+impl<'s> Builder<'s> {
+    fn new(styles: &'s MarkdownStyles) -> Self {
+        Self {
+            styles,
+            lines: Vec::new(),
+            spans: Vec::new(),
+            styles_stack: Vec::new(),
+            containers: Vec::new(),
+            lists: Vec::new(),
+            code_block: false,
+            separate: false,
+        }
+    }
+
+    fn style(&self) -> Style {
+        self.styles_stack
+            .iter()
+            .fold(Style::new(), |acc, &style| acc.patch(style))
+    }
+
+    fn push_style(&mut self, style: Style) {
+        self.styles_stack.push(style);
+    }
+
+    fn pop_style(&mut self) {
+        self.styles_stack.pop();
+    }
+
+    fn prefixes(&self) -> (Vec<Span<'static>>, Vec<Span<'static>>) {
+        let mut prefix = Vec::new();
+        let mut indent = Vec::new();
+        for container in &self.containers {
+            match container {
+                Container::Quote => {
+                    let bar = Span::styled("│ ", self.styles.quote);
+                    prefix.push(bar.clone());
+                    indent.push(bar);
+                }
+                Container::Item { marker, first } => {
+                    let blank = Span::raw(" ".repeat(unicode::display_width(marker)));
+                    if *first {
+                        prefix.push(Span::styled(marker.clone(), self.styles.list_marker));
+                    } else {
+                        prefix.push(blank.clone());
+                    }
+                    indent.push(blank);
+                }
+            }
+        }
+        (prefix, indent)
+    }
+
+    /// adds the empty line between two blocks, if one is due.
+    fn push_separator(&mut self) {
+        if self.separate && !self.lines.is_empty() {
+            let (_, indent) = self.prefixes();
+            self.lines.push(MarkdownLine {
+                prefix: indent.clone(),
+                indent,
+                spans: Vec::new(),
+                kind: LineKind::Wrap,
+            });
+        }
+        self.separate = false;
+    }
+
+    fn push_line(&mut self, spans: Vec<Span<'static>>, kind: LineKind) {
+        self.push_separator();
+        let (prefix, indent) = self.prefixes();
+        self.lines.push(MarkdownLine {
+            prefix,
+            indent,
+            spans,
+            kind,
+        });
+        for container in &mut self.containers {
+            if let Container::Item { first, .. } = container {
+                *first = false;
+            }
+        }
+    }
+
+    /// ends the current line of prose, if there is one.
+    fn flush(&mut self) {
+        if !self.spans.is_empty() {
+            let spans = std::mem::take(&mut self.spans);
+            self.push_line(spans, LineKind::Wrap);
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.code_block {
+            let style = self.styles.code_block;
+            for line in text.lines() {
+                let spans = vec![Span::styled(line.to_string(), style)];
+                self.push_line(spans, LineKind::Code(style));
+            }
+            return;
+        }
+        let span = Span::styled(text.to_string(), self.style());
+        self.spans.push(span);
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) | Event::InlineHtml(text) | Event::Html(text) => self.text(&text),
+            Event::Code(code) | Event::InlineMath(code) | Event::DisplayMath(code) => {
+                let style = self.style().patch(self.styles.code);
+                self.spans.push(Span::styled(code.to_string(), style));
+            }
+            Event::SoftBreak => self.text(" "),
+            Event::HardBreak => self.flush(),
+            Event::Rule => {
+                self.flush();
+                self.separate = true;
+                self.push_line(Vec::new(), LineKind::Rule(self.styles.rule));
+                self.separate = true;
+            }
+            Event::TaskListMarker(done) => self.text(if done { "[x] " } else { "[ ] " }),
+            Event::FootnoteReference(name) => self.text(&format!("[{name}]")),
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Paragraph => self.flush(),
+            Tag::Heading { level, .. } => {
+                self.flush();
+                self.separate = true;
+                let idx = match level {
+                    HeadingLevel::H1 => 0,
+                    HeadingLevel::H2 => 1,
+                    HeadingLevel::H3 => 2,
+                    HeadingLevel::H4 => 3,
+                    HeadingLevel::H5 => 4,
+                    HeadingLevel::H6 => 5,
+                };
+                self.push_style(self.styles.headings[idx]);
+            }
+            Tag::BlockQuote(_) => {
+                self.flush();
+                self.separate = true;
+                self.push_separator();
+                self.containers.push(Container::Quote);
+                self.push_style(self.styles.quote);
+            }
+            Tag::CodeBlock(_) => {
+                self.flush();
+                self.separate = true;
+                self.code_block = true;
+            }
+            Tag::List(start) => {
+                self.flush();
+                if self.lists.is_empty() {
+                    self.separate = true;
+                }
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "• ".to_string(),
+                };
+                self.push_separator();
+                self.containers.push(Container::Item {
+                    marker,
+                    first: true,
+                });
+            }
+            Tag::Emphasis => self.push_style(Style::new().add_modifier(Modifier::ITALIC)),
+            Tag::Strong => self.push_style(Style::new().add_modifier(Modifier::BOLD)),
+            Tag::Strikethrough => self.push_style(Style::new().add_modifier(Modifier::CROSSED_OUT)),
+            Tag::Link { .. } | Tag::Image { .. } => self.push_style(self.styles.link),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => {
+                self.flush();
+                self.separate = true;
+            }
+            TagEnd::Heading(_) => {
+                self.flush();
+                self.pop_style();
+                self.separate = true;
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush();
+                self.containers.pop();
+                self.pop_style();
+                self.separate = true;
+            }
+            TagEnd::CodeBlock => {
+                self.code_block = false;
+                self.separate = true;
+            }
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+                self.separate = self.lists.is_empty();
+            }
+            TagEnd::Item => {
+                self.flush();
+                self.containers.pop();
+                self.separate = false;
+            }
+            TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Link
+            | TagEnd::Image => self.pop_style(),
+            _ => {}
+        }
+    }
+}
+
+impl ElementCtx {
+    /// sizes a [`Markdown`] element to its content on `axis`. called at the end of the fit pass.
+    /// heights are fitted after the grow pass of the widths, so the document is wrapped to the
+    /// width it got.
+    pub(crate) fn fit_markdown(&self, element: Element, axis: Direction) {
+        let mut query = self
+            .world
+            .query_one::<(&mut Markdown, &mut Props, &Width, &Height, &Padding)>(element);
+        let Ok((markdown, props, width, height, padding)) = query.get() else {
+            return;
+        };
+        let horizontal = padding.left + padding.right;
+        match axis {
+            Direction::Horizontal if matches!(**width, Size::Fit) => {
+                props.size.x = markdown.width().saturating_add(horizontal);
+            }
+            Direction::Vertical => {
+                let rows = markdown.rewrap(props.size.x.saturating_sub(horizontal));
+                if matches!(**height, Size::Fit) {
+                    props.size.y = u16::try_from(rows)
+                        .unwrap_or(u16::MAX)
+                        .saturating_add(padding.top + padding.bottom);
+                }
+            }
+            _ => {}
+        }
+    }
+}
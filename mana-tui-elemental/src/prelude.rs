@@ -9,10 +9,12 @@
 pub use crate::layout::TuiElMarker;
 
 pub use crate::layout::{
-    Children, CrossJustify, ElWidget, Element, ElementCtx, Gap, Height, MainJustify, Size, Width,
+    BorderCollapse, Children, CrossJustify, Display, ElWidget, Element, ElementCtx, Gap, Height,
+    Hidden, MainJustify, Size, Width,
 };
 pub use ratatui::{
     layout::Direction,
+    symbols::merge::MergeStrategy,
     text::Text,
     widgets::*,
     widgets::{Block, BorderType, Borders, Padding, Paragraph},
@@ -24,7 +26,25 @@ pub use crate::ui::AsChildren;
 pub use bon;
 
 pub use crate::layout::Center;
-pub use crate::ui::{IntoUiBuilderList, IntoView, IteratorMarker, UiBuilder, View, ui};
+
+pub use crate::animation::{Easing, Property, Time, Transition};
+pub use crate::code_block::{BuiltinHighlighter, CodeBlock, Highlighter};
+pub use crate::color::ColorDepth;
+pub use crate::damage::Damaged;
+#[cfg(feature = "fx")]
+pub use crate::fx::Fx;
+pub use crate::layout::{ByRef, ByRefExt, RenderedArea, Unstyled, UnstyledExt};
+#[cfg(feature = "markdown")]
+pub use crate::markdown::{Markdown, MarkdownStyles};
+pub use crate::scroll::{Overflow, Scroll, ScrollExt};
+pub use crate::shadow::{Backdrop, Shadow};
+pub use crate::text_overflow::TextOverflow;
+pub use crate::theme::{Class, Theme};
+pub use crate::ui::{
+    Cloned, IntoUiBuilderList, IntoView, IteratorMarker, Stateful, StatefulExt, UiBuilder, View, ui,
+};
+pub use crate::unicode::TabWidth;
+pub use crate::virtual_list::{RowHeight, RowStyle, VirtualList, VirtualListState};
 pub use strum;
 pub use tui_scrollview::*;
 
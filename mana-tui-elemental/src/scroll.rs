@@ -0,0 +1,340 @@
+//! # Scroll
+//!
+//! scrolling containers.
+//!
+//! adding [`Scroll`] to an element lays its children out on a canvas sized to their extents. the
+//! element shows the part of the canvas selected by its [`ScrollViewState`], along with
+//! scrollbars depending on the [`Overflow`] of each axis.
+//!
+//! # Example
+//!
+//! ```
+//! # use mana_tui_elemental::prelude::*;
+//! # use ratatui::{buffer::Buffer, layout::Rect};
+//! let mut ctx = ElementCtx::new();
+//! let root = ui(Block::bordered())
+//!     .with((Scroll::vertical(), Width::fixed(20), Height::fixed(5)))
+//!     .children((0..100).map(|idx| ui(Text::raw(format!("item {idx}"))).done()));
+//! let root = ctx.spawn_ui(root);
+//!
+//! # let mut buf = Buffer::empty(Rect::new(0, 0, 20, 5));
+//! ctx.calculate_layout(root, buf.area).unwrap();
+//! let item = ctx.get::<&Children>(root).unwrap()[50];
+//! ctx.scroll_into_view(item);
+//! ctx.render(root, buf.area, &mut buf);
+//! ```
+
+use glam::{U16Vec2, u16vec2};
+use hecs::{Entity, Or, World};
+use ratatui::{
+    layout::{Offset, Position},
+    widgets::Padding,
+};
+use tui_scrollview::{ScrollView, ScrollViewState, ScrollbarVisibility};
+
+use crate::layout::{Children, Element, ElementCtx, Props, rendered_area};
+use crate::virtual_list::{VirtualListState, VirtualRows};
+
+/// defines how an axis of a [`Scroll`] container handles content that does not fit.
+/// defaults to: `Overflow::Auto`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// the content is clipped and the axis does not scroll.
+    Hidden,
+    /// the axis scrolls and the scrollbar is always shown.
+    Scroll,
+    /// the axis scrolls and the scrollbar is shown when the content overflows.
+    #[default]
+    Auto,
+}
+
+impl Overflow {
+    const fn visibility(self) -> ScrollbarVisibility {
+        match self {
+            Overflow::Hidden => ScrollbarVisibility::Never,
+            Overflow::Scroll => ScrollbarVisibility::Always,
+            Overflow::Auto => ScrollbarVisibility::Automatic,
+        }
+    }
+    const fn scrolls(self) -> bool {
+        !matches!(self, Overflow::Hidden)
+    }
+    const fn shows_scrollbar(self, overflows: bool) -> bool {
+        match self {
+            Overflow::Hidden => false,
+            Overflow::Scroll => true,
+            Overflow::Auto => overflows,
+        }
+    }
+}
+
+/// makes an element a scrolling container. the scroll offset is stored in a [`ScrollViewState`]
+/// component, which is added automatically.
+/// defaults to: `Overflow::Auto` on both axes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Scroll {
+    /// overflow on the x axis.
+    pub x: Overflow,
+    /// overflow on the y axis.
+    pub y: Overflow,
+}
+
+impl Scroll {
+    /// create a [`Scroll`] with the given overflow per axis.
+    pub const fn new(x: Overflow, y: Overflow) -> Self {
+        Self { x, y }
+    }
+    /// scrolls on the y axis only.
+    pub const fn vertical() -> Self {
+        Self::new(Overflow::Hidden, Overflow::Auto)
+    }
+    /// scrolls on the x axis only.
+    pub const fn horizontal() -> Self {
+        Self::new(Overflow::Auto, Overflow::Hidden)
+    }
+}
+
+/// canvas geometry of a [`Scroll`] container, computed after layout.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ScrollCanvas {
+    /// size of the canvas the children are rendered to.
+    pub(crate) size: U16Vec2,
+    /// part of the inner area not covered by scrollbars.
+    pub(crate) viewport: U16Vec2,
+    /// largest offset on each axis.
+    pub(crate) max_offset: U16Vec2,
+}
+
+impl ElementCtx {
+    pub(crate) fn size_scroll_canvases(&mut self) {
+        let containers: Vec<_> = self
+            .world
+            .query_mut::<(Entity, &Scroll, &Props, &Padding, &Children)>()
+            .into_iter()
+            .map(|(entity, &scroll, &props, &padding, children)| {
+                (entity, scroll, props, padding, children.clone())
+            })
+            .collect();
+        for (entity, scroll, props, padding, children) in containers {
+            let inner_origin = props.position + u16vec2(padding.left, padding.top);
+            let inner_size = props.inner_size_from_padding(&padding);
+            let extents = self
+                .displayed_children(&children)
+                .into_iter()
+                .filter_map(|child| {
+                    let child = self.world.get::<&Props>(child).ok()?;
+                    Some((child.position + child.size).saturating_sub(inner_origin))
+                })
+                .fold(U16Vec2::ZERO, U16Vec2::max);
+
+            let mut size = inner_size.max(extents);
+            if !scroll.x.scrolls() {
+                size.x = inner_size.x;
+            }
+            if !scroll.y.scrolls() {
+                size.y = inner_size.y;
+            }
+            let mut viewport = inner_size;
+            if scroll.y.shows_scrollbar(size.y > inner_size.y) {
+                viewport.x = viewport.x.saturating_sub(1);
+            }
+            if scroll.x.shows_scrollbar(size.x > inner_size.x) {
+                viewport.y = viewport.y.saturating_sub(1);
+            }
+            let mut max_offset = size.saturating_sub(viewport);
+            if !scroll.x.scrolls() {
+                max_offset.x = 0;
+            }
+            if !scroll.y.scrolls() {
+                max_offset.y = 0;
+            }
+
+            let scrollview = ScrollView::new(ratatui::layout::Size {
+                width: size.x,
+                height: size.y,
+            })
+            .horizontal_scrollbar_visibility(scroll.x.visibility())
+            .vertical_scrollbar_visibility(scroll.y.visibility());
+            _ = self.world.insert(
+                entity,
+                (
+                    scrollview,
+                    ScrollCanvas {
+                        size,
+                        viewport,
+                        max_offset,
+                    },
+                ),
+            );
+        }
+    }
+}
+
+/// scrolling operations. implemented for [`World`], so they can be used on [`ElementCtx`] too.
+///
+/// all methods need the layout to be calculated first.
+pub trait ScrollExt {
+    /// scrolls a [`Scroll`] container by `delta` cells, clamped to its content. a
+    /// [`VirtualList`][crate::virtual_list::VirtualList] scrolls by `delta.y` rows.
+    ///
+    /// returns whether the offset changed.
+    fn scroll_by(&mut self, container: Element, delta: Offset) -> bool;
+
+    /// scrolls every [`Scroll`] container that holds `element` so that it becomes visible.
+    ///
+    /// returns whether any offset changed.
+    fn scroll_into_view(&mut self, element: Element) -> bool;
+
+    /// scrolls a [`VirtualList`][crate::virtual_list::VirtualList] so that `row` is fully
+    /// visible after the next layout.
+    ///
+    /// returns whether the offset changed.
+    fn scroll_to_row(&mut self, list: Element, row: usize) -> bool;
+
+    /// scrolls the innermost [`Scroll`] container or
+    /// [`VirtualList`][crate::virtual_list::VirtualList] under `position`, in terminal
+    /// coordinates. if it cannot scroll any further, its parent containers are tried instead.
+    ///
+    /// needs the tree to be rendered first. returns whether any offset changed.
+    fn scroll_at(&mut self, position: Position, delta: Offset) -> bool;
+}
+
+impl ScrollExt for World {
+    fn scroll_by(&mut self, container: Element, delta: Offset) -> bool {
+        if let Ok(max_offset) = self
+            .get::<&VirtualRows>(container)
+            .map(|rows| rows.max_offset)
+        {
+            let Ok(mut state) = self.get::<&mut VirtualListState>(container) else {
+                return false;
+            };
+            let current = state.offset();
+            let offset = current
+                .saturating_add_signed(delta.y as isize)
+                .min(max_offset);
+            state.set_offset(offset);
+            return offset != current;
+        }
+        let Ok(canvas) = self.get::<&ScrollCanvas>(container).map(|canvas| *canvas) else {
+            return false;
+        };
+        let Ok(mut state) = self.get::<&mut ScrollViewState>(container) else {
+            return false;
+        };
+        let current = state.offset();
+        let offset = Position {
+            x: offset_by(current.x, delta.x, canvas.max_offset.x),
+            y: offset_by(current.y, delta.y, canvas.max_offset.y),
+        };
+        state.set_offset(offset);
+        offset != current
+    }
+
+    fn scroll_into_view(&mut self, element: Element) -> bool {
+        let Ok(target) = self.get::<&Props>(element).map(|props| *props) else {
+            return false;
+        };
+        let containers: Vec<_> = self
+            .query::<(Entity, &Props, &Padding, &ScrollCanvas)>()
+            .iter()
+            .filter(|&(container, ..)| {
+                container != element && is_ancestor(self, container, element)
+            })
+            .map(|(container, &props, &padding, &canvas)| (container, props, padding, canvas))
+            .collect();
+        let mut changed = false;
+        for (container, props, padding, canvas) in containers {
+            let inner_origin = props.position + u16vec2(padding.left, padding.top);
+            let start = target.position.saturating_sub(inner_origin);
+            let Ok(mut state) = self.get::<&mut ScrollViewState>(container) else {
+                continue;
+            };
+            let current = state.offset();
+            let offset = Position {
+                x: reveal(current.x, start.x, target.size.x, canvas.viewport.x)
+                    .min(canvas.max_offset.x),
+                y: reveal(current.y, start.y, target.size.y, canvas.viewport.y)
+                    .min(canvas.max_offset.y),
+            };
+            state.set_offset(offset);
+            changed |= offset != current;
+        }
+        changed
+    }
+
+    fn scroll_to_row(&mut self, list: Element, row: usize) -> bool {
+        let Ok((visible, shown, max_offset)) = self
+            .get::<&VirtualRows>(list)
+            .map(|rows| (rows.visible.clone(), rows.shown.max(1), rows.max_offset))
+        else {
+            return false;
+        };
+        let Ok(mut state) = self.get::<&mut VirtualListState>(list) else {
+            return false;
+        };
+        let current = state.offset();
+        let offset = if row < visible.start {
+            row
+        } else if row >= visible.start + shown {
+            row + 1 - shown
+        } else {
+            current
+        };
+        let offset = offset.min(max_offset);
+        state.set_offset(offset);
+        offset != current
+    }
+
+    fn scroll_at(&mut self, position: Position, delta: Offset) -> bool {
+        let containers: Vec<_> = self
+            .query::<(Entity, Or<&ScrollCanvas, &VirtualRows>)>()
+            .iter()
+            .filter(|&(container, _)| {
+                rendered_area(self, container).is_some_and(|area| area.contains(position))
+            })
+            .map(|(container, _)| container)
+            .collect();
+        // the innermost container is the one with the most candidates above it
+        let mut containers: Vec<_> = containers
+            .iter()
+            .map(|&container| {
+                let depth = containers
+                    .iter()
+                    .filter(|&&other| is_ancestor(self, other, container))
+                    .count();
+                (container, depth)
+            })
+            .collect();
+        containers.sort_by_key(|&(_, depth)| std::cmp::Reverse(depth));
+        containers
+            .into_iter()
+            .any(|(container, _)| self.scroll_by(container, delta))
+    }
+}
+
+fn offset_by(offset: u16, delta: i32, max: u16) -> u16 {
+    let offset = i32::from(offset).saturating_add(delta);
+    offset.clamp(0, i32::from(max)) as u16
+}
+
+/// smallest change to `offset` that fits `start..start + len` in the viewport, preferring the
+/// start if it does not fit.
+fn reveal(offset: u16, start: u16, len: u16, viewport: u16) -> u16 {
+    let end = start.saturating_add(len);
+    if start < offset || len > viewport {
+        start
+    } else if end > offset.saturating_add(viewport) {
+        end - viewport
+    } else {
+        offset
+    }
+}
+
+fn is_ancestor(world: &World, ancestor: Element, element: Element) -> bool {
+    let Ok(children) = world.get::<&Children>(ancestor) else {
+        return false;
+    };
+    children
+        .iter()
+        .any(|&child| child == element || is_ancestor(world, child, element))
+}
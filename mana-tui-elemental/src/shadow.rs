@@ -0,0 +1,171 @@
+//! # Shadow
+//!
+//! drop shadows and backdrops for floating elements like dialogs and popups.
+//!
+//! both effects change the colors of cells that were already rendered, right before the element
+//! they belong to renders. colors are converted to the [`ColorDepth`] resource.
+//!
+//! # Example
+//!
+//! ```
+//! # use mana_tui_elemental::prelude::*;
+//! # use ratatui::{buffer::Buffer, layout::Rect};
+//! let mut ctx = ElementCtx::new();
+//! let root = ui(Block::new())
+//!     .with((Center, Width::grow(), Height::grow()))
+//!     .children((
+//!         ui(Block::bordered().title_top("dialog"))
+//!             .with((Shadow::default(), Backdrop::dim(0.6), Width::fixed(20), Height::fixed(5))),
+//!     ));
+//! let root = ctx.spawn_ui(root);
+//!
+//! # let mut buf = Buffer::empty(Rect::new(0, 0, 40, 10));
+//! ctx.calculate_layout(root, buf.area).unwrap();
+//! ctx.render(root, buf.area, &mut buf);
+//! ```
+
+use mana_tui_utils::resource::Resources;
+use ratatui::{
+    buffer::{Buffer, Cell},
+    layout::{Offset, Rect},
+    style::Modifier,
+};
+
+use crate::color::{self, ColorDepth};
+use crate::layout::{Element, ElementCtx};
+
+/// darkens the cells under an element, offset from it. the shadow is drawn right before the
+/// element renders, so elements rendered after it are drawn on top.
+/// defaults to: `Shadow { offset: Offset { x: 1, y: 1 }, darken: 0.5 }`
+///
+/// # Example
+///
+/// the cells darkened by the default shadow are marked with `░`
+/// ```plaintext
+/// ┌──────┐
+/// │dialog│░
+/// └──────┘░
+///  ░░░░░░░░
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shadow {
+    /// how far the shadow is moved from the element.
+    pub offset: Offset,
+    /// how much the cells are darkened, from 0 to 1.
+    pub darken: f32,
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        Self {
+            offset: Offset { x: 1, y: 1 },
+            darken: 0.5,
+        }
+    }
+}
+
+impl Shadow {
+    /// create a shadow offset by one cell to the bottom right, darkening by `darken`.
+    pub const fn new(darken: f32) -> Self {
+        Self {
+            offset: Offset { x: 1, y: 1 },
+            darken,
+        }
+    }
+
+    /// sets how far the shadow is moved from the element.
+    #[must_use]
+    pub const fn offset(mut self, offset: Offset) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+/// dims everything rendered before the element, so a modal stands out from the ui behind it.
+/// cells on the terminal's default background are darkened to black.
+/// defaults to: `Backdrop { darken: 0.5, desaturate: 0.0 }`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backdrop {
+    /// how much the cells are darkened, from 0 to 1.
+    pub darken: f32,
+    /// how much the cells are moved towards gray, from 0 to 1.
+    pub desaturate: f32,
+}
+
+impl Default for Backdrop {
+    fn default() -> Self {
+        Self {
+            darken: 0.5,
+            desaturate: 0.0,
+        }
+    }
+}
+
+impl Backdrop {
+    /// create a backdrop that darkens by `darken`.
+    pub const fn dim(darken: f32) -> Self {
+        Self {
+            darken,
+            desaturate: 0.0,
+        }
+    }
+
+    /// create a backdrop that moves colors towards gray by `desaturate`.
+    pub const fn desaturate(desaturate: f32) -> Self {
+        Self {
+            darken: 0.0,
+            desaturate,
+        }
+    }
+}
+
+/// the color that cells on the terminal's default background are darkened from.
+const DEFAULT_BG: (u8, u8, u8) = (0, 0, 0);
+
+/// changes the colors of a cell. text on the terminal's default color is dimmed instead, and a
+/// darkened cell without a background gets a concrete dark one, so it doesn't stay at the
+/// terminal's default.
+fn shade_cell(cell: &mut Cell, darken: f32, desaturate: f32, depth: ColorDepth) {
+    match color::to_rgb(cell.fg) {
+        Some(fg) => cell.fg = depth.convert(color::shade(fg, darken, desaturate)),
+        None if darken > 0.0 => cell.modifier.insert(Modifier::DIM),
+        None => {}
+    }
+    let bg = color::to_rgb(cell.bg).or((darken > 0.0).then_some(DEFAULT_BG));
+    if let Some(bg) = bg {
+        cell.bg = depth.convert(color::shade(bg, darken, desaturate));
+    }
+}
+
+impl ElementCtx {
+    /// applies the [`Backdrop`] and [`Shadow`] of an element that is about to render in `area`.
+    pub(crate) fn render_shadows(&self, element: Element, area: Rect, buf: &mut Buffer) {
+        let backdrop = self.world.get::<&Backdrop>(element).ok().map(|b| *b);
+        let shadow = self.world.get::<&Shadow>(element).ok().map(|s| *s);
+        if backdrop.is_none() && shadow.is_none() {
+            return;
+        }
+        let depth = self
+            .world
+            .get_resource::<&ColorDepth>()
+            .map_or_else(|_| ColorDepth::default(), |depth| *depth);
+
+        if let Some(backdrop) = backdrop {
+            for position in buf.area.positions() {
+                let cell = &mut buf[position];
+                shade_cell(cell, backdrop.darken, backdrop.desaturate, depth);
+            }
+        }
+        if let Some(shadow) = shadow {
+            let shadow_area = area.offset(shadow.offset).intersection(buf.area);
+            for position in shadow_area.positions() {
+                // the element covers its own area
+                if area.contains(position) {
+                    continue;
+                }
+                let cell = &mut buf[position];
+                shade_cell(cell, shadow.darken, 0.0, depth);
+            }
+        }
+    }
+}
@@ -0,0 +1,302 @@
+//! # Text Overflow
+//!
+//! handling of text that does not fit its element.
+//!
+//! adding [`TextOverflow`] to a [`Text`], [`Line`], [`Span`], [`Paragraph`] or [`CodeBlock`]
+//! element renders it at its full size first, then fits every row to the element, marking the rows
+//! that were cut. wide characters are never split in half.
+//!
+//! [`Text`]: ratatui::text::Text
+//! [`Line`]: ratatui::text::Line
+//! [`Span`]: ratatui::text::Span
+//! [`Paragraph`]: ratatui::widgets::Paragraph
+//! [`CodeBlock`]: crate::code_block::CodeBlock
+//!
+//! # Example
+//!
+//! ```
+//! # use mana_tui_elemental::prelude::*;
+//! # use ratatui::{buffer::Buffer, layout::Rect};
+//! let mut ctx = ElementCtx::new();
+//! let root = ui(Text::raw("~/projects/mana-tui/readme.md"))
+//!     .with((TextOverflow::EllipsisMiddle, Width::fixed(16)));
+//! let root = ctx.spawn_ui(root);
+//!
+//! # let mut buf = Buffer::empty(Rect::new(0, 0, 16, 1));
+//! ctx.calculate_layout(root, buf.area).unwrap();
+//! ctx.render(root, buf.area, &mut buf);
+//! assert_eq!(buf, Buffer::with_lines(["~/projec…adme.md"]));
+//! ```
+
+use glam::{U16Vec2, u16vec2};
+use ratatui::{
+    buffer::{Buffer, Cell},
+    layout::Rect,
+    style::{Modifier, Style},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::layout::{ByRef, Element, ElementCtx};
+use crate::ui::TextQuery;
+use crate::unicode;
+
+/// defines how a text element shows content that does not fit its size.
+/// defaults to: `TextOverflow::Clip`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextOverflow {
+    /// the text is cut at the edge of the element.
+    #[default]
+    Clip,
+    /// the end of the text is replaced with `…`.
+    Ellipsis,
+    /// the middle of the text is replaced with `…`, keeping both ends. useful for paths.
+    EllipsisMiddle,
+    /// the start of the text is replaced with `…`.
+    EllipsisStart,
+    /// the last cells before the edge are dimmed.
+    Fade,
+}
+
+/// number of cells dimmed by [`TextOverflow::Fade`].
+const FADE_WIDTH: usize = 3;
+const ELLIPSIS: &str = "…";
+
+impl TextQuery<'_> {
+    /// size the content needs to render fully, given the width of the element.
+    fn size(self, width: u16) -> U16Vec2 {
+        let (width, height) = match self {
+            TextQuery::Text(text) | TextQuery::TextByRef(ByRef { widget: text, .. }) => {
+                (unicode::text_width(text), text.height())
+            }
+            TextQuery::Paragraph(paragraph)
+            | TextQuery::ParagraphByRef(ByRef {
+                widget: paragraph, ..
+            }) => {
+                // wrapping paragraphs only overflow on the y axis
+                let wraps = paragraph.line_count(width) > paragraph.line_count(u16::MAX);
+                let width = if wraps {
+                    usize::from(width)
+                } else {
+                    paragraph.line_width()
+                };
+                let height = paragraph.line_count(width.try_into().unwrap_or(u16::MAX));
+                (width, height)
+            }
+            TextQuery::Line(line) => (unicode::line_width(line), 1),
+            TextQuery::Span(span) => (unicode::display_width(&span.content), 1),
+            TextQuery::CodeBlock(code) => (code.width().into(), code.height().into()),
+        };
+        u16vec2(
+            width.try_into().unwrap_or(u16::MAX),
+            height.try_into().unwrap_or(u16::MAX),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Glyph {
+    cell: Cell,
+    width: u16,
+}
+
+impl Glyph {
+    fn is_blank(&self) -> bool {
+        self.cell.symbol().trim().is_empty()
+    }
+
+    fn ellipsis(next_to: Option<&Glyph>) -> Self {
+        let mut cell = next_to.map(|glyph| glyph.cell.clone()).unwrap_or_default();
+        cell.set_symbol(ELLIPSIS);
+        Self { cell, width: 1 }
+    }
+}
+
+impl ElementCtx {
+    /// renders a text element with a [`TextOverflow`] that does not fit `area`.
+    ///
+    /// returns `false` if the element should be rendered normally.
+    pub(crate) fn render_text_overflow(
+        &self,
+        element: Element,
+        render: fn(&ElementCtx, Element, Rect, &mut Buffer),
+        area: Rect,
+        buf: &mut Buffer,
+    ) -> bool {
+        let Ok(overflow) = self
+            .world
+            .get::<&TextOverflow>(element)
+            .map(|overflow| *overflow)
+        else {
+            return false;
+        };
+        let content = match self.world.query_one::<TextQuery>(element).get() {
+            Ok(text) => text.size(area.width),
+            Err(_) => return false,
+        };
+        if area.is_empty() || (content.x <= area.width && content.y <= area.height) {
+            return false;
+        }
+
+        // render the whole content on top of what is already under the element
+        let scratch_area = Rect::new(
+            area.x,
+            area.y,
+            content.x.max(area.width),
+            content.y.max(area.height),
+        );
+        let mut scratch = Buffer::empty(scratch_area);
+        for (x, y) in scratch_area.positions().map(|pos| (pos.x, pos.y)) {
+            let under = (x.min(area.right() - 1), y.min(area.bottom() - 1));
+            scratch[(x, y)] = buf[under].clone();
+        }
+        render(self, element, scratch_area, &mut scratch);
+
+        let overflows_y = content.y > area.height;
+        for y in area.top()..area.bottom() {
+            let glyphs = row_glyphs(&scratch, y);
+            let end = glyphs
+                .iter()
+                .rposition(|glyph| !glyph.is_blank())
+                .map_or(0, |idx| idx + 1);
+            let content_width: u16 = glyphs[..end].iter().map(|glyph| glyph.width).sum();
+            let is_last = y == area.bottom() - 1;
+            let fitted = if content_width > area.width {
+                fit_row(&glyphs[..end], area.width, overflow)
+            } else if is_last && overflows_y {
+                mark_last_row(&glyphs[..end], area.width, overflow)
+            } else {
+                None
+            };
+            match fitted {
+                Some(fitted) => write_row(&fitted, &scratch, buf, area, y),
+                None => {
+                    for x in area.left()..area.right() {
+                        buf[(x, y)] = scratch[(x, y)].clone();
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+fn row_glyphs(buf: &Buffer, y: u16) -> Vec<Glyph> {
+    let mut glyphs = Vec::new();
+    let mut x = buf.area.left();
+    while x < buf.area.right() {
+        let cell = &buf[(x, y)];
+        let width = u16::try_from(cell.symbol().width()).unwrap_or(1).max(1);
+        glyphs.push(Glyph {
+            cell: cell.clone(),
+            width,
+        });
+        x = x.saturating_add(width);
+    }
+    glyphs
+}
+
+/// longest run of glyphs from the start that fits in `width`.
+fn prefix(glyphs: &[Glyph], width: u16) -> &[Glyph] {
+    let mut used = 0;
+    let len = glyphs
+        .iter()
+        .take_while(|glyph| {
+            used += glyph.width;
+            used <= width
+        })
+        .count();
+    &glyphs[..len]
+}
+
+/// longest run of glyphs from the end that fits in `width`.
+fn suffix(glyphs: &[Glyph], width: u16) -> &[Glyph] {
+    let mut used = 0;
+    let len = glyphs
+        .iter()
+        .rev()
+        .take_while(|glyph| {
+            used += glyph.width;
+            used <= width
+        })
+        .count();
+    &glyphs[glyphs.len() - len..]
+}
+
+fn fit_row(glyphs: &[Glyph], width: u16, overflow: TextOverflow) -> Option<Vec<Glyph>> {
+    let room = width.saturating_sub(1);
+    let fitted = match overflow {
+        TextOverflow::Clip => prefix(glyphs, width).to_vec(),
+        TextOverflow::Ellipsis => {
+            let start = prefix(glyphs, room);
+            let mut fitted = start.to_vec();
+            fitted.push(Glyph::ellipsis(glyphs.get(start.len())));
+            fitted
+        }
+        TextOverflow::EllipsisStart => {
+            let content = glyphs.iter().position(|glyph| !glyph.is_blank())?;
+            let end = suffix(&glyphs[content..], room);
+            let mut fitted = vec![Glyph::ellipsis(end.first())];
+            fitted.extend_from_slice(end);
+            fitted
+        }
+        TextOverflow::EllipsisMiddle => {
+            let start = prefix(glyphs, room.div_ceil(2));
+            let start_width: u16 = start.iter().map(|glyph| glyph.width).sum();
+            let end = suffix(&glyphs[start.len()..], room - start_width);
+            let mut fitted = start.to_vec();
+            fitted.push(Glyph::ellipsis(glyphs.get(start.len())));
+            fitted.extend_from_slice(end);
+            fitted
+        }
+        TextOverflow::Fade => {
+            let mut fitted = prefix(glyphs, width).to_vec();
+            fade(&mut fitted);
+            fitted
+        }
+    };
+    Some(fitted)
+}
+
+/// marks the last row shown when there are more rows below it.
+fn mark_last_row(glyphs: &[Glyph], width: u16, overflow: TextOverflow) -> Option<Vec<Glyph>> {
+    match overflow {
+        TextOverflow::Clip => None,
+        TextOverflow::Ellipsis | TextOverflow::EllipsisMiddle | TextOverflow::EllipsisStart => {
+            let start = prefix(glyphs, width.saturating_sub(1));
+            let mut fitted = start.to_vec();
+            fitted.push(Glyph::ellipsis(start.last()));
+            Some(fitted)
+        }
+        TextOverflow::Fade => {
+            let mut fitted = glyphs.to_vec();
+            fade(&mut fitted);
+            Some(fitted)
+        }
+    }
+}
+
+fn fade(glyphs: &mut [Glyph]) {
+    let start = glyphs.len().saturating_sub(FADE_WIDTH);
+    for glyph in &mut glyphs[start..] {
+        glyph
+            .cell
+            .set_style(Style::new().add_modifier(Modifier::DIM));
+    }
+}
+
+fn write_row(glyphs: &[Glyph], scratch: &Buffer, buf: &mut Buffer, area: Rect, y: u16) {
+    let mut x = area.left();
+    for glyph in glyphs {
+        buf[(x, y)] = glyph.cell.clone();
+        // cells covered by wide characters are reset, like ratatui does
+        for hidden in x + 1..x + glyph.width {
+            buf[(hidden, y)].reset();
+        }
+        x += glyph.width;
+    }
+    for x in x..area.right() {
+        let mut cell = scratch[(x, y)].clone();
+        cell.set_symbol(" ");
+        buf[(x, y)] = cell;
+    }
+}
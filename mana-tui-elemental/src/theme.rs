@@ -0,0 +1,359 @@
+//! # Theme
+//!
+//! class based styling for elements.
+//!
+//! styles are registered by name in a [`Theme`] resource and elements opt into them with the
+//! [`Class`] component. pseudo-states like `"button:focused"` are resolved through marker
+//! components registered with [`Theme::state`].
+//!
+//! # Example
+//!
+//! ```
+//! # use mana_tui_elemental::prelude::*;
+//! # use mana_tui_utils::resource::Resources;
+//! # use ratatui::{buffer::Buffer, layout::Rect, style::{Color, Style}};
+//! struct Focused;
+//!
+//! let mut ctx = ElementCtx::new();
+//! ctx.insert_resource(
+//!     Theme::new()
+//!         .class("button", Style::new().fg(Color::Black).bg(Color::Cyan))
+//!         .class("button:focused", Style::new().bg(Color::Magenta))
+//!         .state::<Focused>("focused"),
+//! );
+//!
+//! let root = ctx.spawn_ui(ui(Text::raw("ok")).with((Class("button"), Focused)));
+//! # let mut buf = Buffer::empty(Rect::new(0, 0, 10, 1));
+//! ctx.calculate_layout(root, buf.area).unwrap();
+//! ctx.render(root, buf.area, &mut buf);
+//! ```
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use hecs::{Component, Entity, EntityRef};
+use mana_tui_utils::resource::Resources;
+use ratatui::{
+    style::{Color, Style},
+    widgets::{Block, BorderType, Padding},
+};
+
+#[cfg(any(feature = "toml", feature = "ron"))]
+mod file;
+#[cfg(all(feature = "watch", any(feature = "toml", feature = "ron")))]
+mod watch;
+
+#[cfg(any(feature = "toml", feature = "ron"))]
+pub use file::ThemeError;
+#[cfg(all(feature = "watch", any(feature = "toml", feature = "ron")))]
+pub use watch::ThemeWatcher;
+
+use crate::layout::{ElementCtx, Props};
+
+/// tags an element with one or more theme classes, separated by whitespace.
+///
+/// # Example
+///
+/// ```
+/// # use mana_tui_elemental::prelude::*;
+/// ui(Block::new()).with((Class("card danger"),));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Class(pub &'static str);
+
+impl Class {
+    /// iterates over the individual class names.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> {
+        self.0.split_whitespace()
+    }
+}
+
+/// resource that maps class names to styles.
+///
+/// insert it with [`Resources::insert_resource`] and swap it at runtime with
+/// [`Resources::insert_or_update_resource`]. the styles are resolved again on every
+/// [`ElementCtx::calculate_layout`], and by [`ElementCtx::render`] when the theme was swapped
+/// since, so switching themes does not require respawning the view.
+#[derive(Debug, Clone, Default)]
+pub struct Theme {
+    classes: HashMap<String, Style>,
+    /// the `"class:state"` styles, by class and then by state.
+    pseudo: HashMap<String, HashMap<String, Style>>,
+    states: Vec<(String, StatePredicate)>,
+    colors: HashMap<String, Color>,
+    padding: HashMap<String, Padding>,
+    borders: HashMap<String, BorderType>,
+    /// changes whenever the theme does, so [`ElementCtx::render`] can tell a swapped theme apart.
+    revision: u64,
+}
+
+/// the source of [`Theme::revision`]s.
+static REVISIONS: AtomicU64 = AtomicU64::new(1);
+
+/// resource with the revision of the theme [`ElementCtx::apply_theme`] last applied.
+struct AppliedTheme(u64);
+
+type StatePredicate = fn(&EntityRef) -> bool;
+
+fn has_component<T: Component>(entity: &EntityRef) -> bool {
+    entity.has::<T>()
+}
+
+impl Theme {
+    /// create an empty theme.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// registers the style for a class. use `"class:state"` to style a pseudo-state.
+    ///
+    /// registering the same name twice replaces the previous style.
+    #[must_use]
+    pub fn class(mut self, name: impl Into<String>, style: Style) -> Self {
+        self.insert_class(name, style);
+        self
+    }
+
+    /// like [`Theme::class`], but through a mutable reference.
+    pub fn insert_class(&mut self, name: impl Into<String>, style: Style) {
+        let name = name.into();
+        if let Some((class, state)) = name.split_once(':') {
+            self.pseudo
+                .entry(class.to_owned())
+                .or_default()
+                .insert(state.to_owned(), style);
+        }
+        self.classes.insert(name, style);
+        self.touch();
+    }
+
+    /// gives the theme a new revision.
+    fn touch(&mut self) {
+        self.revision = REVISIONS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// registers a pseudo-state. `"class:name"` styles apply to elements that have the
+    /// component `T`.
+    #[must_use]
+    pub fn state<T: Component>(mut self, name: impl Into<String>) -> Self {
+        self.states.push((name.into(), has_component::<T>));
+        self.touch();
+        self
+    }
+
+    /// gets the style registered for the given name.
+    pub fn get(&self, name: &str) -> Option<Style> {
+        self.classes.get(name).copied()
+    }
+
+    /// registers a named color.
+    #[must_use]
+    pub fn with_color(mut self, name: impl Into<String>, color: Color) -> Self {
+        self.colors.insert(name.into(), color);
+        self.touch();
+        self
+    }
+
+    /// gets a named color.
+    pub fn color(&self, name: &str) -> Option<Color> {
+        self.colors.get(name).copied()
+    }
+
+    /// registers a named [`Padding`] preset.
+    #[must_use]
+    pub fn with_padding(mut self, name: impl Into<String>, padding: Padding) -> Self {
+        self.padding.insert(name.into(), padding);
+        self.touch();
+        self
+    }
+
+    /// gets a named [`Padding`] preset.
+    pub fn padding(&self, name: &str) -> Option<Padding> {
+        self.padding.get(name).copied()
+    }
+
+    /// registers a named [`BorderType`] preset.
+    #[must_use]
+    pub fn with_border_type(mut self, name: impl Into<String>, border_type: BorderType) -> Self {
+        self.borders.insert(name.into(), border_type);
+        self.touch();
+        self
+    }
+
+    /// gets a named [`BorderType`] preset.
+    pub fn border_type(&self, name: &str) -> Option<BorderType> {
+        self.borders.get(name).copied()
+    }
+
+    /// resolves the style of an element. classes are applied in order, each followed by its
+    /// active pseudo-states.
+    pub fn resolve(&self, class: &Class, entity: &EntityRef) -> Style {
+        class.names().fold(Style::new(), |style, name| {
+            let style = style.patch(self.get(name).unwrap_or_default());
+            let Some(pseudo) = self.pseudo.get(name) else {
+                return style;
+            };
+            self.states
+                .iter()
+                .filter_map(|(state, is_active)| pseudo.get(state).filter(|_| is_active(entity)))
+                .copied()
+                .fold(style, Style::patch)
+        })
+    }
+}
+
+/// what the theme last wrote over a value, and the value it was written over.
+#[derive(Debug, Clone, Copy)]
+struct Themed<T> {
+    base: T,
+    themed: T,
+}
+
+impl<T: Copy + PartialEq> Themed<T> {
+    /// the base under `current`. a value that differs from what the theme wrote was changed
+    /// inline, so it becomes the new base.
+    fn base(last: Option<Self>, current: T) -> T {
+        match last {
+            Some(last) if last.themed == current => last.base,
+            _ => current,
+        }
+    }
+}
+
+/// what the theme last applied to an element. inline styles always win over classes.
+#[derive(Debug, Clone, Copy)]
+struct BaseStyle {
+    style: Themed<Style>,
+    padding: Themed<Padding>,
+    border_type: Option<BorderType>,
+}
+
+impl Theme {
+    /// the padding preset of the last class that has one.
+    fn resolve_padding(&self, class: &Class) -> Option<Padding> {
+        class.names().filter_map(|name| self.padding(name)).last()
+    }
+
+    /// the border type preset of the last class that has one.
+    fn resolve_border_type(&self, class: &Class) -> Option<BorderType> {
+        class
+            .names()
+            .filter_map(|name| self.border_type(name))
+            .last()
+    }
+}
+
+impl ElementCtx {
+    /// resolves the [`Class`] of every element against the [`Theme`] resource and applies the
+    /// result through [`Props::set_style`].
+    ///
+    /// the style of the widget is captured as its base the first time, and again whenever it
+    /// differs from what the theme last wrote, so inline style changes are kept.
+    ///
+    /// padding presets are added to the [`Padding`] of the element, inside the borders, and border
+    /// type presets are set on [`Block`] widgets. a border type is only written when the preset
+    /// changes, and stays when a new theme no longer has one.
+    ///
+    /// called automatically by [`ElementCtx::calculate_layout`], and by [`ElementCtx::render`]
+    /// when the theme changed since. does nothing if there is no theme.
+    pub fn apply_theme(&mut self) {
+        #[cfg(all(feature = "watch", any(feature = "toml", feature = "ron")))]
+        self.reload_theme();
+
+        let Ok(revision) = self
+            .world
+            .get_resource::<&Theme>()
+            .map(|theme| theme.revision)
+        else {
+            return;
+        };
+        self.apply_theme_where(|_| true);
+        self.world.insert_or_update_resource(AppliedTheme(revision));
+    }
+
+    /// applies the theme to the elements `filter` accepts, like [`ElementCtx::apply_theme`].
+    pub(crate) fn apply_theme_where(&mut self, filter: impl Fn(Entity) -> bool) {
+        let Ok(theme) = self.world.get_resource::<&Theme>() else {
+            return;
+        };
+        let mut query = self
+            .world
+            .query::<(Entity, &Class, &Props, Option<&Padding>, Option<&BaseStyle>)>();
+        let themed: Vec<_> = query
+            .iter()
+            .filter(|&(entity, ..)| filter(entity))
+            .map(|(entity, class, props, padding, last)| {
+                let current = (props.get_style)(&self.world, entity).unwrap_or_default();
+                let base = Themed::base(last.map(|last| last.style), current);
+                let entity_ref = self.world.entity(entity).unwrap();
+                let style = theme.resolve(class, &entity_ref).patch(base);
+
+                let current = padding.copied().unwrap_or_default();
+                let base_padding = Themed::base(last.map(|last| last.padding), current);
+                let padding = theme
+                    .resolve_padding(class)
+                    .map_or(base_padding, |preset| add_padding(base_padding, preset));
+
+                let border_type = theme.resolve_border_type(class);
+                let border_changed =
+                    border_type.is_some() && border_type != last.and_then(|last| last.border_type);
+                let themed = BaseStyle {
+                    style: Themed {
+                        base,
+                        themed: style,
+                    },
+                    padding: Themed {
+                        base: base_padding,
+                        themed: padding,
+                    },
+                    border_type: border_type.or(last.and_then(|last| last.border_type)),
+                };
+                (entity, props.set_style, themed, border_changed)
+            })
+            .collect();
+        drop(query);
+        drop(theme);
+
+        for (entity, set_style, themed, border_changed) in themed {
+            set_style(&mut self.world, entity, themed.style.themed);
+            if let Ok(mut padding) = self.world.get::<&mut Padding>(entity) {
+                *padding = themed.padding.themed;
+            }
+            if let Some(border_type) = themed.border_type.filter(|_| border_changed)
+                && let Ok(mut block) = self.world.get::<&mut Block>(entity)
+            {
+                *block = std::mem::take(&mut *block).border_type(border_type);
+            }
+            let updated = self
+                .world
+                .get::<&mut BaseStyle>(entity)
+                .map(|mut last| *last = themed)
+                .is_ok();
+            if !updated {
+                _ = self.world.insert_one(entity, themed);
+            }
+        }
+    }
+
+    /// applies the theme if it is not the one [`ElementCtx::apply_theme`] last applied.
+    pub(crate) fn apply_changed_theme(&mut self) {
+        let changed = self.world.get_resource::<&Theme>().is_ok_and(|theme| {
+            !self
+                .world
+                .get_resource::<&AppliedTheme>()
+                .is_ok_and(|applied| applied.0 == theme.revision)
+        });
+        if changed {
+            self.apply_theme();
+        }
+    }
+}
+
+const fn add_padding(a: Padding, b: Padding) -> Padding {
+    Padding {
+        left: a.left.saturating_add(b.left),
+        right: a.right.saturating_add(b.right),
+        top: a.top.saturating_add(b.top),
+        bottom: a.bottom.saturating_add(b.bottom),
+    }
+}
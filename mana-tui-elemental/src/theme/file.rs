@@ -0,0 +1,236 @@
+//! loading themes from `toml` and `ron` files.
+
+use std::{collections::HashMap, path::Path, str::FromStr};
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    widgets::{BorderType, Padding},
+};
+use serde::Deserialize;
+
+use crate::theme::Theme;
+
+/// error returned when loading a [`Theme`] from a file.
+#[derive(Debug, thiserror::Error)]
+pub enum ThemeError {
+    /// the file could not be read.
+    #[error("failed to read theme file: {0}")]
+    Io(#[from] std::io::Error),
+    /// the file extension is not `.toml` or `.ron`, or the feature for it is disabled.
+    #[error("unsupported theme format: {0}")]
+    UnsupportedFormat(String),
+    /// the `toml` file is invalid.
+    #[cfg(feature = "toml")]
+    #[error("invalid toml theme: {0}")]
+    Toml(#[from] toml::de::Error),
+    /// the `ron` file is invalid.
+    #[cfg(feature = "ron")]
+    #[error("invalid ron theme: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    /// a color is neither a named color nor a valid color.
+    #[error("unknown color `{0}`")]
+    UnknownColor(String),
+    /// a border type does not exist.
+    #[error("unknown border type `{0}`")]
+    UnknownBorderType(String),
+    /// a modifier does not exist.
+    #[error("unknown modifier `{0}`")]
+    UnknownModifier(String),
+    /// the file could not be watched.
+    #[cfg(feature = "watch")]
+    #[error("failed to watch theme file: {0}")]
+    Watch(#[from] notify::Error),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ThemeFile {
+    colors: HashMap<String, String>,
+    padding: HashMap<String, PaddingPreset>,
+    borders: HashMap<String, String>,
+    classes: HashMap<String, ClassEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PaddingPreset {
+    Uniform(u16),
+    Symmetric([u16; 2]),
+    Sides {
+        #[serde(default)]
+        left: u16,
+        #[serde(default)]
+        right: u16,
+        #[serde(default)]
+        top: u16,
+        #[serde(default)]
+        bottom: u16,
+    },
+}
+
+impl From<PaddingPreset> for Padding {
+    fn from(value: PaddingPreset) -> Self {
+        match value {
+            PaddingPreset::Uniform(value) => Padding::uniform(value),
+            PaddingPreset::Symmetric([x, y]) => Padding::symmetric(x, y),
+            PaddingPreset::Sides {
+                left,
+                right,
+                top,
+                bottom,
+            } => Padding::new(left, right, top, bottom),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ClassEntry {
+    fg: Option<String>,
+    bg: Option<String>,
+    underline_color: Option<String>,
+    modifiers: Vec<String>,
+    remove_modifiers: Vec<String>,
+}
+
+impl Theme {
+    /// parses a theme from a `toml` string. see [`Theme::load`] for the format.
+    ///
+    /// # Errors
+    ///
+    /// errors if the string is not a valid theme.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(source: &str) -> Result<Self, ThemeError> {
+        toml::from_str::<ThemeFile>(source)?.try_into()
+    }
+
+    /// parses a theme from a `ron` string. see [`Theme::load`] for the format.
+    ///
+    /// # Errors
+    ///
+    /// errors if the string is not a valid theme.
+    #[cfg(feature = "ron")]
+    pub fn from_ron_str(source: &str) -> Result<Self, ThemeError> {
+        ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_str::<ThemeFile>(source)?
+            .try_into()
+    }
+
+    /// loads a theme from a file, picking the format from the extension.
+    ///
+    /// # Format
+    ///
+    /// ```toml
+    /// [colors]
+    /// accent = "#89b4fa"
+    /// text = "black"
+    ///
+    /// [padding]
+    /// card = [2, 1]
+    /// dense = 0
+    /// wide = { left = 4, right = 4 }
+    ///
+    /// [borders]
+    /// card = "rounded"
+    ///
+    /// [classes.button]
+    /// fg = "text"
+    /// bg = "accent"
+    /// modifiers = ["bold"]
+    ///
+    /// [classes."button:focused"]
+    /// bg = "magenta"
+    /// ```
+    ///
+    /// colors can be names from `[colors]`, ratatui color names, indices or hex codes.
+    /// padding presets are either uniform, `[horizontal, vertical]` or per side.
+    /// `ron` files use the same structure.
+    ///
+    /// # Errors
+    ///
+    /// errors if the file cannot be read, the format is unsupported or the theme is invalid.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ThemeError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::from_toml_str(&source),
+            #[cfg(feature = "ron")]
+            Some("ron") => Self::from_ron_str(&source),
+            _ => Err(ThemeError::UnsupportedFormat(path.display().to_string())),
+        }
+    }
+}
+
+impl TryFrom<ThemeFile> for Theme {
+    type Error = ThemeError;
+
+    fn try_from(file: ThemeFile) -> Result<Self, Self::Error> {
+        let mut theme = Theme::new();
+        for (name, color) in file.colors {
+            let color =
+                Color::from_str(&color).map_err(|_| ThemeError::UnknownColor(color.clone()))?;
+            theme = theme.with_color(name, color);
+        }
+        for (name, padding) in file.padding {
+            theme = theme.with_padding(name, padding.into());
+        }
+        for (name, border_type) in file.borders {
+            theme = theme.with_border_type(name, parse_border_type(&border_type)?);
+        }
+        for (name, class) in file.classes {
+            let style = class_style(&theme, class)?;
+            theme.insert_class(name, style);
+        }
+        Ok(theme)
+    }
+}
+
+fn class_style(theme: &Theme, class: ClassEntry) -> Result<Style, ThemeError> {
+    let mut style = Style::new();
+    if let Some(fg) = class.fg {
+        style = style.fg(parse_color(theme, &fg)?);
+    }
+    if let Some(bg) = class.bg {
+        style = style.bg(parse_color(theme, &bg)?);
+    }
+    if let Some(underline_color) = class.underline_color {
+        style = style.underline_color(parse_color(theme, &underline_color)?);
+    }
+    for modifier in class.modifiers {
+        style = style.add_modifier(parse_modifier(&modifier)?);
+    }
+    for modifier in class.remove_modifiers {
+        style = style.remove_modifier(parse_modifier(&modifier)?);
+    }
+    Ok(style)
+}
+
+fn parse_color(theme: &Theme, value: &str) -> Result<Color, ThemeError> {
+    theme
+        .color(value)
+        .or_else(|| Color::from_str(value).ok())
+        .ok_or_else(|| ThemeError::UnknownColor(value.to_string()))
+}
+
+fn parse_modifier(value: &str) -> Result<Modifier, ThemeError> {
+    Modifier::from_name(&value.to_uppercase().replace('-', "_"))
+        .ok_or_else(|| ThemeError::UnknownModifier(value.to_string()))
+}
+
+/// accepts both the variant name (`QuadrantOutside`) and kebab or snake case (`quadrant-outside`).
+fn parse_border_type(value: &str) -> Result<BorderType, ThemeError> {
+    let pascal: String = value
+        .split(['-', '_'])
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect();
+    BorderType::from_str(&pascal).map_err(|_| ThemeError::UnknownBorderType(value.to_string()))
+}
@@ -0,0 +1,112 @@
+//! live reloading of theme files.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Mutex, PoisonError,
+        mpsc::{self, Receiver},
+    },
+    time::Duration,
+};
+
+use mana_tui_utils::resource::Resources;
+use notify::{Config, PollWatcher, RecursiveMode, Watcher};
+
+use crate::{
+    layout::ElementCtx,
+    theme::{Theme, ThemeError},
+};
+
+/// resource that watches a theme file for changes.
+///
+/// inserted by [`ElementCtx::watch_theme`]. while it exists, [`ElementCtx::apply_theme`] swaps
+/// the [`Theme`] resource whenever the file changes.
+pub struct ThemeWatcher {
+    path: PathBuf,
+    _watcher: PollWatcher,
+    events: Mutex<Receiver<notify::Result<notify::Event>>>,
+}
+
+impl std::fmt::Debug for ThemeWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ThemeWatcher")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ThemeWatcher {
+    /// starts watching the file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// errors if the file cannot be watched.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, ThemeError> {
+        let path = path.as_ref().to_path_buf();
+        let (tx, rx) = mpsc::channel();
+        let config = Config::default()
+            .with_poll_interval(Duration::from_millis(250))
+            .with_compare_contents(true);
+        let mut watcher = PollWatcher::new(tx, config)?;
+        watcher.watch(&path, RecursiveMode::NonRecursive)?;
+        Ok(Self {
+            path,
+            _watcher: watcher,
+            events: Mutex::new(rx),
+        })
+    }
+
+    /// the watched path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// reloads the theme if the file changed since the last call.
+    pub fn poll(&self) -> Option<Result<Theme, ThemeError>> {
+        let events = self.events.lock().unwrap_or_else(PoisonError::into_inner);
+        let changed = events
+            .try_iter()
+            .filter_map(Result::ok)
+            .any(|event| !event.kind.is_access());
+        changed.then(|| Theme::load(&self.path))
+    }
+}
+
+impl ElementCtx {
+    /// loads the theme at `path` and keeps it in sync with the file.
+    ///
+    /// the new theme replaces the current [`Theme`] resource. pseudo-states registered with
+    /// [`Theme::state`] on the current theme are kept, on load and on every reload.
+    ///
+    /// # Errors
+    ///
+    /// errors if the theme cannot be loaded or the file cannot be watched.
+    pub fn watch_theme(&mut self, path: impl AsRef<Path>) -> Result<(), ThemeError> {
+        let theme = Theme::load(&path)?;
+        let watcher = ThemeWatcher::new(path)?;
+        self.swap_theme(theme);
+        self.world.insert_or_update_resource(watcher);
+        Ok(())
+    }
+
+    pub(super) fn reload_theme(&mut self) {
+        let Ok(watcher) = self.world.get_resource::<&ThemeWatcher>() else {
+            return;
+        };
+        let Some(result) = watcher.poll() else {
+            return;
+        };
+        drop(watcher);
+        match result {
+            Ok(theme) => self.swap_theme(theme),
+            Err(err) => tracing::warn!(%err, "failed to reload theme, keeping the previous one"),
+        }
+    }
+
+    fn swap_theme(&mut self, mut theme: Theme) {
+        if let Ok(current) = self.world.get_resource::<&Theme>() {
+            theme.states.clone_from(&current.states);
+        }
+        self.world.insert_or_update_resource(theme);
+    }
+}
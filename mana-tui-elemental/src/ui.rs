@@ -18,23 +18,34 @@
 //!
 //! ```
 
-use std::{any::TypeId, borrow::Cow, collections::VecDeque, sync::Arc};
+use std::{any::TypeId, borrow::Cow, collections::VecDeque, marker::PhantomData, sync::Arc};
 
+use derive_more as d;
 use glam::U16Vec2;
-use hecs::{CommandBuffer, DynamicBundle, Entity, EntityBuilder, Or, Query, World};
+use hecs::{CommandBuffer, Component, DynamicBundle, Entity, EntityBuilder, Or, Query, World};
+use mana_tui_utils::resource::Resources;
 use ratatui::{
     buffer::Buffer,
     layout::{Direction, Rect},
     style::Style,
     text::{Line, Span, Text},
-    widgets::{Block, Padding, Paragraph},
+    widgets::{Block, Padding, Paragraph, StatefulWidget},
 };
 use tracing::{Level, enabled, instrument};
+use tui_scrollview::{ScrollView, ScrollViewState};
 
+use crate::code_block::CodeBlock;
+use crate::damage;
 use crate::layout::{
-    Center, Children, CrossJustify, ElWidget, Element, ElementCtx, Gap, Height, MainJustify,
-    ManaComponent, Props, Size, TuiElMarker, Width,
+    ByRef, Center, Children, CrossJustify, ElWidget, Element, ElementCtx, Gap, Height, MainJustify,
+    ManaComponent, Props, Size, Spacing, TuiElMarker, Width,
 };
+use crate::scroll::Scroll;
+use crate::unicode::{self, ExpandTabs, TabWidth};
+use crate::virtual_list::{VirtualList, VirtualListState};
+
+#[cfg(feature = "markdown")]
+use crate::markdown::Markdown;
 
 /// create a ui element.
 ///
@@ -157,6 +168,11 @@ where
                 None
             }
         }
+        fn fingerprint_system<M, W: ElWidget<M>>(ctx: &World, entity: hecs::Entity) -> Option<u64> {
+            ctx.get::<&W>(entity)
+                .ok()
+                .map(|widget| damage::fingerprint(&*widget))
+        }
         builder.add(self);
         builder.add_bundle((
             TuiElMarker,
@@ -167,6 +183,159 @@ where
                 render: render_system::<M, W>,
                 set_style: set_style_system::<M, W>,
                 get_style: get_style_system::<M, W>,
+                fingerprint: fingerprint_system::<M, W>,
+            },
+        ));
+        builder
+    }
+}
+
+/// wraps a [`StatefulWidget`] so it can be used as an element.
+///
+/// the state is stored as a sibling component on the same entity and is passed mutably at render
+/// time. it starts out as `W::State::default()`, add your own state with [`UiBuilder::with`] to
+/// replace it. the state can be queried like any other component.
+///
+/// since stateful widgets are not always [`Styled`][ratatui::style::Styled], the style of the
+/// element is applied to its area before the widget renders.
+///
+/// the widget renders through a shared reference, which most ratatui widgets like [`List`] and
+/// [`Table`] implement [`StatefulWidget`] for. wrap widgets that only render by value in
+/// [`Cloned`], with [`StatefulExt::stateful_cloned`].
+///
+/// [`List`]: ratatui::widgets::List
+/// [`Table`]: ratatui::widgets::Table
+///
+/// # Example
+///
+/// ```
+/// # use mana_tui_elemental::prelude::*;
+/// let mut ctx = ElementCtx::new();
+/// let root = ui(List::new(["a", "b", "c"]).highlight_symbol(">").stateful())
+///     .with((ListState::default().with_selected(Some(1)),));
+/// let root = ctx.spawn_ui(root);
+///
+/// ctx.get::<&mut ListState>(root).unwrap().select_next();
+/// ```
+#[derive(Debug, Clone, Default, d::Deref, d::DerefMut)]
+pub struct Stateful<W> {
+    /// the wrapped widget.
+    #[deref]
+    #[deref_mut]
+    pub widget: W,
+    /// style applied to the element's area before rendering.
+    pub style: Style,
+}
+
+impl<W> Stateful<W> {
+    /// wraps the widget.
+    pub fn new(widget: W) -> Self {
+        Self {
+            widget,
+            style: Style::default(),
+        }
+    }
+}
+
+/// extension trait for turning [`StatefulWidget`]s into elements.
+pub trait StatefulExt: StatefulWidget + Sized {
+    /// wraps the widget in [`Stateful`].
+    fn stateful(self) -> Stateful<Self> {
+        Stateful::new(self)
+    }
+
+    /// wraps the widget in [`Stateful`], cloning it every frame to render it.
+    fn stateful_cloned(self) -> Stateful<Cloned<Self>>
+    where
+        Self: Clone,
+    {
+        Stateful::new(Cloned(self))
+    }
+}
+
+impl<W: StatefulWidget> StatefulExt for W {}
+
+/// renders a [`StatefulWidget`] that only renders by value, by cloning it. used for widgets in
+/// [`Stateful`] that do not implement [`StatefulWidget`] for a shared reference.
+#[derive(Debug, Clone, Default, d::Deref, d::DerefMut)]
+pub struct Cloned<W>(pub W);
+
+impl<W: StatefulWidget> StatefulWidget for Cloned<W> {
+    type State = W::State;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        self.0.render(area, buf, state);
+    }
+}
+
+impl<W: StatefulWidget + Clone> StatefulWidget for &Cloned<W> {
+    type State = W::State;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        self.0.clone().render(area, buf, state);
+    }
+}
+
+/// marker for the [`IntoView`] implementation of [`Stateful`].
+pub struct StatefulMarker;
+
+impl<W> IntoView<StatefulMarker> for Stateful<W>
+where
+    W: StatefulWidget + std::fmt::Debug + Component,
+    for<'a> &'a W: StatefulWidget<State = W::State>,
+    W::State: Component + Default,
+{
+    fn into_view(self) -> View {
+        let mut builder = View::new();
+        fn render_system<W>(ctx: &ElementCtx, entity: hecs::Entity, area: Rect, buf: &mut Buffer)
+        where
+            W: StatefulWidget + Component,
+            for<'a> &'a W: StatefulWidget<State = W::State>,
+            W::State: Component + Default,
+        {
+            let Ok(stateful) = ctx.world.get::<&Stateful<W>>(entity) else {
+                return;
+            };
+            buf.set_style(area, stateful.style);
+            let mut state = ctx.world.get::<&mut W::State>(entity);
+            let mut default_state = W::State::default();
+            StatefulWidget::render(
+                &stateful.widget,
+                area,
+                buf,
+                state.as_deref_mut().unwrap_or(&mut default_state),
+            );
+        }
+        fn set_style_system<W: Component>(ctx: &mut World, entity: hecs::Entity, style: Style) {
+            if let Ok(mut stateful) = ctx.get::<&mut Stateful<W>>(entity) {
+                stateful.style = style;
+            }
+        }
+        fn get_style_system<W: Component>(ctx: &World, entity: hecs::Entity) -> Option<Style> {
+            ctx.get::<&Stateful<W>>(entity)
+                .ok()
+                .map(|stateful| stateful.style)
+        }
+        fn fingerprint_system<W>(ctx: &World, entity: hecs::Entity) -> Option<u64>
+        where
+            W: std::fmt::Debug + Component,
+        {
+            ctx.get::<&Stateful<W>>(entity)
+                .ok()
+                .map(|stateful| damage::fingerprint(&*stateful))
+        }
+        builder.add(self);
+        builder.add(W::State::default());
+        builder.add_bundle((
+            TuiElMarker,
+            Props {
+                typeid: TypeId::of::<Stateful<W>>(),
+                size: U16Vec2::default(),
+                position: U16Vec2::default(),
+                render: render_system::<W>,
+                set_style: set_style_system::<W>,
+                get_style: get_style_system::<W>,
+                fingerprint: fingerprint_system::<W>,
             },
         ));
         builder
@@ -378,15 +547,87 @@ impl_into_ui_builder_list_for_tuples!(0 U0, 1 U1, 2 U2, 3 U3, 4 U4, 5 U5, 6 U6,
 impl_into_ui_builder_list_for_tuples!(0 U0, 1 U1, 2 U2, 3 U3, 4 U4, 5 U5, 6 U6, 7 U7, 8 U8, 9 U9, 10 U10);
 impl_into_ui_builder_list_for_tuples!(0 U0, 1 U1, 2 U2, 3 U3, 4 U4, 5 U5, 6 U6, 7 U7, 8 U8, 9 U9, 10 U10, 11 U11);
 
+/// trait that marks a type can take the nested markup of a `ui!` element.
+///
+/// implemented for widgets, which take it as their children, and for subviews with a `children`
+/// parameter, which `#[subview]` implements it for.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not take children",
+    note = "subviews take nested markup through a `children` parameter"
+)]
+pub trait WithChildren<C, M> {
+    /// make the conversion to view, with `children` nested inside.
+    fn with_children(self, children: C) -> View;
+}
+
+/// marker for the [`WithChildren`] implementation of widgets.
+pub struct WidgetMarker<M, CM>(PhantomData<(M, CM)>);
+
+impl<W, M, C, CM> WithChildren<C, WidgetMarker<M, CM>> for W
+where
+    W: IntoView<M>,
+    C: IntoUiBuilderList<CM>,
+{
+    fn with_children(self, children: C) -> View {
+        let mut view = self.into_view();
+        view.add(ChildrenBuilders(children.into_list().collect()));
+        view
+    }
+}
+
+/// marker for the [`WithChildren`] implementations of subviews.
+pub struct SubviewMarker;
+
+/// trait that marks a subview has a `children` parameter. it is implemented for no type.
+///
+/// `#[subview]` implements [`WithChildren`] for subviews without a `children` parameter too, with
+/// this as a bound, so nesting markup in them is an error instead of a match for the
+/// implementation of widgets.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not take children",
+    note = "subviews take nested markup through a `children` parameter"
+)]
+pub trait TakesChildren {}
+
+/// trait that marks a type can take a struct of props, set all at once with `<Name ..{props} />`
+/// in `ui!`.
+///
+/// `#[subview]` implements it for subviews with `#[prop(default)]` parameters, whose props struct
+/// holds those parameters.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not take `{P}` as props",
+    note = "only subviews with `#[prop(default)]` parameters take props"
+)]
+pub trait Spread<P> {
+    /// the builder with the props set.
+    type Output;
+
+    /// sets every field of `props`.
+    fn spread(self, props: P) -> Self::Output;
+}
+
 pub(crate) struct ChildrenBuilders(pub(crate) Box<[EntityBuilder]>);
 
+/// the widgets that are sized by their text.
+#[derive(Query)]
+pub(crate) enum TextQuery<'a> {
+    Text(&'a Text<'a>),
+    Paragraph(&'a Paragraph<'a>),
+    Line(&'a Line<'a>),
+    Span(&'a Span<'a>),
+    TextByRef(&'a ByRef<Text<'a>>),
+    ParagraphByRef(&'a ByRef<Paragraph<'a>>),
+    CodeBlock(&'a CodeBlock),
+}
+
 #[instrument(skip(world))]
-fn process_ui_system(world: &mut ElementCtx) {
+fn process_ui_system(world: &mut ElementCtx, root: Element) {
     let mut to_process: VecDeque<Element> = world
         .query_mut::<(Entity, &ChildrenBuilders)>()
         .into_iter()
         .map(|(e, _)| e)
         .collect();
+    let mut spawned = vec![root];
 
     while let Some(node) = to_process.pop_front() {
         if let Ok(builders) = world.remove_one::<ChildrenBuilders>(node) {
@@ -406,6 +647,7 @@ fn process_ui_system(world: &mut ElementCtx) {
                     if has_children {
                         to_process.push_back(entity);
                     }
+                    spawned.push(entity);
                     entity
                 })
                 .collect();
@@ -443,12 +685,34 @@ fn process_ui_system(world: &mut ElementCtx) {
         }
     }
 
-    #[derive(Query)]
-    enum TextQuery<'a> {
-        Text(&'a Text<'a>),
-        Paragraph(&'a Paragraph<'a>),
-        Line(&'a Line<'a>),
-        Span(&'a Span<'a>),
+    let tab_width = world
+        .get_resource::<&TabWidth>()
+        .map_or_else(|_| TabWidth::default(), |tab_width| *tab_width);
+    for &node in &spawned {
+        if let Ok(expand) = world.remove_one::<ExpandTabs>(node) {
+            expand.build(world, node, tab_width);
+        }
+    }
+    // text that was spawned before has its tabs expanded already
+    for &node in &spawned {
+        let Ok(entity) = world.entity(node) else {
+            continue;
+        };
+        if let Some(mut text) = entity.get::<&mut Text>() {
+            unicode::expand_tabs_in_text(&mut text, tab_width);
+        }
+        if let Some(mut text) = entity.get::<&mut ByRef<Text>>() {
+            unicode::expand_tabs_in_text(&mut text, tab_width);
+        }
+        if let Some(mut line) = entity.get::<&mut Line>() {
+            unicode::expand_tabs_in_line(&mut line, tab_width);
+        }
+        if let Some(mut span) = entity.get::<&mut Span>() {
+            unicode::expand_tabs_in_span(&mut span, 0, tab_width);
+        }
+        if let Some(mut code) = entity.get::<&mut CodeBlock>() {
+            code.expand_tabs(tab_width);
+        }
     }
 
     for (node, text_query, width, height) in
@@ -458,10 +722,13 @@ fn process_ui_system(world: &mut ElementCtx) {
             tracing::trace!(?node, "processing default size for text",);
         }
         let new_size = match text_query {
-            TextQuery::Text(text) => Some((text.width(), text.height())),
-            TextQuery::Paragraph(_) => None,
-            TextQuery::Line(line) => Some((line.width(), 1)),
-            TextQuery::Span(span) => Some((span.width(), 1)),
+            TextQuery::Text(text) | TextQuery::TextByRef(ByRef { widget: text, .. }) => {
+                Some((unicode::text_width(text), text.height()))
+            }
+            TextQuery::Paragraph(_) | TextQuery::ParagraphByRef(_) => None,
+            TextQuery::Line(line) => Some((unicode::line_width(line), 1)),
+            TextQuery::Span(span) => Some((unicode::display_width(&span.content), 1)),
+            TextQuery::CodeBlock(code) => Some((code.width().into(), code.height().into())),
         };
         if width.is_none() {
             if let Some((width, _)) = new_size {
@@ -485,7 +752,13 @@ fn process_ui_system(world: &mut ElementCtx) {
     for (node, _) in query.iter() {
         let entity = world.entity(node).unwrap();
         if !entity.has::<Width>() {
-            buffer.insert_one(node, Width(Size::Fit));
+            // markdown wraps to the width it is given
+            #[cfg(feature = "markdown")]
+            let fit = !entity.has::<Markdown>();
+            #[cfg(not(feature = "markdown"))]
+            let fit = true;
+            let width = if fit { Width(Size::Fit) } else { Width::grow() };
+            buffer.insert_one(node, width);
         }
         if !entity.has::<Height>() {
             buffer.insert_one(node, Height(Size::Fit));
@@ -502,12 +775,25 @@ fn process_ui_system(world: &mut ElementCtx) {
         if !entity.has::<Gap>() {
             buffer.insert_one(node, Gap::default());
         }
+        if !entity.has::<Spacing>() {
+            buffer.insert_one(node, Spacing::default());
+        }
         if !entity.has::<Padding>() {
             buffer.insert_one(node, Padding::default());
         }
         if !entity.has::<Children>() {
             buffer.insert_one(node, Children::None);
         }
+        let is_scroll = entity.has::<Scroll>() || entity.has::<ScrollView>();
+        if is_scroll && !entity.has::<Scroll>() {
+            buffer.insert_one(node, Scroll::default());
+        }
+        if is_scroll && !entity.has::<ScrollViewState>() {
+            buffer.insert_one(node, ScrollViewState::default());
+        }
+        if entity.has::<VirtualList>() && !entity.has::<VirtualListState>() {
+            buffer.insert_one(node, VirtualListState::default());
+        }
     }
     drop(query);
 
@@ -528,7 +814,7 @@ impl ElementCtx {
         let mut ui = ui.into();
         let ui = ui.build();
         let root = self.spawn(ui);
-        process_ui_system(self);
+        process_ui_system(self, root);
         root
     }
 
@@ -0,0 +1,182 @@
+//! # Unicode
+//!
+//! measuring text the way it is drawn to the terminal.
+//!
+//! widths are measured per grapheme, so CJK characters, emoji sequences and combining marks take
+//! up exactly the cells ratatui draws them in. tabs are expanded to spaces when an element is
+//! spawned, using the [`TabWidth`] resource. widgets that do not give their text back, like
+//! [`Paragraph`], are built from their text with [`expand_tabs`] instead.
+//!
+//! [`Paragraph`]: ratatui::widgets::Paragraph
+//!
+//! # Example
+//!
+//! ```
+//! # use mana_tui_elemental::prelude::*;
+//! # use mana_tui_utils::resource::Resources;
+//! let mut ctx = ElementCtx::new();
+//! ctx.insert_resource(TabWidth(2));
+//! let root = ctx.spawn_ui(ui(Text::raw("a\tb")));
+//! assert!(matches!(**ctx.get::<&Width>(root).unwrap(), Size::Fixed(3)));
+//! ```
+
+use derive_more as d;
+use hecs::{Component, Entity, World};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::{Line, Span, Text},
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::ui::{__ui_internal, IntoView, UiBuilder, ui_builder};
+
+/// resource that sets how many columns a tab stop spans. tabs in [`Text`], [`Line`], [`Span`] and
+/// [`CodeBlock`] elements, and in the text given to [`expand_tabs`], are expanded to spaces up to
+/// the next tab stop when they are spawned.
+///
+/// [`CodeBlock`]: crate::code_block::CodeBlock
+/// defaults to: `TabWidth(4)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, d::Deref)]
+pub struct TabWidth(pub u16);
+
+impl Default for TabWidth {
+    fn default() -> Self {
+        Self(4)
+    }
+}
+
+/// number of cells `content` takes up when drawn. control characters take up none.
+pub fn display_width(content: &str) -> usize {
+    content
+        .graphemes(true)
+        .filter(|grapheme| !grapheme.contains(char::is_control))
+        .map(UnicodeWidthStr::width)
+        .sum()
+}
+
+pub(crate) fn line_width(line: &Line) -> usize {
+    line.spans
+        .iter()
+        .map(|span| display_width(&span.content))
+        .sum()
+}
+
+pub(crate) fn text_width(text: &Text) -> usize {
+    text.lines.iter().map(line_width).max().unwrap_or_default()
+}
+
+type BuildWidget = Box<dyn FnOnce(Text<'static>, &mut World, Entity) + Send + Sync>;
+
+/// builds the widget of an element from its text once the element is spawned, see
+/// [`expand_tabs`].
+pub(crate) struct ExpandTabs {
+    text: Text<'static>,
+    build: BuildWidget,
+}
+
+impl ExpandTabs {
+    /// expands the tabs in the text and replaces the widget of `element` with the one built
+    /// from it.
+    pub(crate) fn build(mut self, world: &mut World, element: Entity, tab_width: TabWidth) {
+        expand_tabs_in_text(&mut self.text, tab_width);
+        (self.build)(self.text, world, element);
+    }
+}
+
+/// creates an element whose widget is built from `text` once it is spawned, after its tabs are
+/// expanded with the [`TabWidth`] of the context. for widgets like [`Paragraph`] that keep their
+/// text private, so the tabs in it cannot be expanded later. the `ui!` macro uses it for
+/// `<Paragraph>` text with tabs.
+///
+/// [`Paragraph`]: ratatui::widgets::Paragraph
+///
+/// # Example
+///
+/// ```
+/// # use mana_tui_elemental::prelude::*;
+/// # use mana_tui_elemental::unicode::expand_tabs;
+/// # use ratatui::{buffer::Buffer, layout::Rect};
+/// let mut ctx = ElementCtx::new();
+/// let root = ctx.spawn_ui(expand_tabs("a\tb", Paragraph::new));
+/// # let mut buf = Buffer::empty(Rect::new(0, 0, 6, 1));
+/// ctx.calculate_layout(root, buf.area).unwrap();
+/// ctx.render(root, buf.area, &mut buf);
+/// assert_eq!(buf, Buffer::with_lines(["a   b "]));
+/// ```
+pub fn expand_tabs<W, M>(
+    text: impl Into<Text<'static>>,
+    build: impl FnOnce(Text<'static>) -> W + Send + Sync + 'static,
+) -> UiBuilder<ui_builder::Empty>
+where
+    W: IntoView<M> + Default + Component,
+{
+    let mut view = W::default().into_view();
+    view.add(ExpandTabs {
+        text: text.into(),
+        build: Box::new(move |text, world, element| {
+            _ = world.insert_one(element, build(text));
+        }),
+    });
+    __ui_internal(view)
+}
+
+pub(crate) fn expand_tabs_in_text(text: &mut Text, tab_width: TabWidth) {
+    for line in &mut text.lines {
+        expand_tabs_in_line(line, tab_width);
+    }
+}
+
+pub(crate) fn expand_tabs_in_line(line: &mut Line, tab_width: TabWidth) {
+    let mut column = 0;
+    for span in &mut line.spans {
+        column = expand_tabs_in_span(span, column, tab_width);
+    }
+}
+
+/// expands the tabs in `span`, which starts at `column`. returns the column after it.
+pub(crate) fn expand_tabs_in_span(span: &mut Span, column: usize, tab_width: TabWidth) -> usize {
+    if !span.content.contains('\t') {
+        return column + display_width(&span.content);
+    }
+    let tab_width = usize::from(*tab_width);
+    let mut column = column;
+    let mut expanded = String::with_capacity(span.content.len());
+    for grapheme in span.content.graphemes(true) {
+        if grapheme == "\t" {
+            let spaces = match tab_width {
+                0 => 0,
+                _ => tab_width - column % tab_width,
+            };
+            expanded.extend(std::iter::repeat_n(' ', spaces));
+            column += spaces;
+        } else {
+            expanded.push_str(grapheme);
+            column += display_width(grapheme);
+        }
+    }
+    span.content = expanded.into();
+    column
+}
+
+/// blanks wide characters that cross the left or right edge of `area`, so a glyph never
+/// straddles the boundary between two elements.
+pub(crate) fn clear_straddling(buf: &mut Buffer, area: Rect) {
+    let area = area.intersection(buf.area);
+    if area.is_empty() {
+        return;
+    }
+    for y in area.top()..area.bottom() {
+        if area.left() > buf.area.left() {
+            let before = &mut buf[(area.left() - 1, y)];
+            if before.symbol().width() > 1 {
+                before.set_symbol(" ");
+            }
+        }
+        let last = &mut buf[(area.right() - 1, y)];
+        if last.symbol().width() > 1 {
+            last.set_symbol(" ");
+        }
+    }
+}
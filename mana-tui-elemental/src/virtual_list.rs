@@ -0,0 +1,435 @@
+//! # Virtual List
+//!
+//! lists that only spawn the rows in view.
+//!
+//! adding [`VirtualList`] to an element makes it build its children from a row function. only the
+//! rows that fit in the element are spawned and laid out, starting from the first row selected by
+//! its [`VirtualListState`]. rows that stay in view keep their entities between layouts, rows
+//! that scroll out of view are despawned.
+//!
+//! virtual lists can be scrolled with [`ScrollExt`][crate::scroll::ScrollExt], one row per cell.
+//!
+//! # Example
+//!
+//! ```
+//! # use mana_tui_elemental::prelude::*;
+//! # use ratatui::{buffer::Buffer, layout::Rect};
+//! let mut ctx = ElementCtx::new();
+//! let root = ui(Block::bordered()).with((
+//!     VirtualList::new(100_000, |idx| ui(Text::raw(format!("line {idx}")))),
+//!     Width::fixed(20),
+//!     Height::fixed(10),
+//! ));
+//! let root = ctx.spawn_ui(root);
+//!
+//! # let mut buf = Buffer::empty(Rect::new(0, 0, 20, 10));
+//! ctx.calculate_layout(root, buf.area).unwrap();
+//! ctx.scroll_to_row(root, 50_000);
+//! ctx.calculate_layout(root, buf.area).unwrap();
+//! ctx.render(root, buf.area, &mut buf);
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    ops::Range,
+    sync::Arc,
+};
+
+use glam::{U16Vec2, u16vec2};
+use hecs::Entity;
+use ratatui::{
+    layout::{Direction, Rect},
+    style::Style,
+    widgets::Padding,
+};
+
+use crate::{
+    layout::{Children, Element, ElementCtx, Gap, Height, Props, Width},
+    ui::View,
+};
+
+type RowBuilder = Arc<dyn Fn(usize) -> View + Send + Sync>;
+
+/// makes an element a virtualized list of `len` rows, built on demand by a row function.
+///
+/// the element should not be [`Size::Fit`][crate::layout::Size::Fit] on the y axis, since its
+/// rows do not count towards its size. the [`Gap`] of the element is used between rows, and its
+/// [`RowStyle`] is applied to them.
+#[derive(Clone)]
+pub struct VirtualList {
+    len: usize,
+    row: RowBuilder,
+    row_height: RowHeight,
+}
+
+impl std::fmt::Debug for VirtualList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VirtualList")
+            .field("len", &self.len)
+            .field("row_height", &self.row_height)
+            .finish_non_exhaustive()
+    }
+}
+
+impl VirtualList {
+    /// create a list of `len` rows. `row` is called with the index of each row that comes into
+    /// view.
+    pub fn new<V: Into<View>>(
+        len: usize,
+        row: impl Fn(usize) -> V + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            len,
+            row: Arc::new(move |idx| row(idx).into()),
+            row_height: RowHeight::default(),
+        }
+    }
+
+    /// sets how the height of each row is found.
+    #[must_use]
+    pub fn row_height(mut self, row_height: RowHeight) -> Self {
+        self.row_height = row_height;
+        self
+    }
+
+    /// the number of rows.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// whether the list has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// changes the number of rows, for lists that grow over time like logs. rows that are
+    /// already spawned are kept.
+    pub fn set_len(&mut self, len: usize) {
+        self.len = len;
+    }
+}
+
+/// defines the height of the rows in a [`VirtualList`].
+/// defaults to: `RowHeight::Fixed(1)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowHeight {
+    /// every row is this many cells high, and at least one. rows that grow on the y axis are
+    /// stretched to it.
+    Fixed(u16),
+    /// rows are as high as their content. the row function is called for every row measured,
+    /// so this is slower when scrolling far.
+    Measured,
+}
+
+impl Default for RowHeight {
+    fn default() -> Self {
+        Self::Fixed(1)
+    }
+}
+
+/// style patched onto the widget of every row of a [`VirtualList`] when it is spawned. the
+/// style of the row itself wins.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RowStyle(pub Style);
+
+/// the first row shown by a [`VirtualList`]. added automatically.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct VirtualListState {
+    offset: usize,
+}
+
+impl VirtualListState {
+    /// create a state that starts at the given row.
+    pub const fn new(offset: usize) -> Self {
+        Self { offset }
+    }
+
+    /// the index of the first row shown.
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// sets the first row shown. it is clamped to the rows on the next layout.
+    pub const fn set_offset(&mut self, offset: usize) {
+        self.offset = offset;
+    }
+}
+
+/// rows spawned by a [`VirtualList`], keyed by row index.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct VirtualRows {
+    rows: HashMap<usize, Element>,
+    /// rows that are at least partially in view.
+    pub(crate) visible: Range<usize>,
+    /// number of rows at the start of `visible` that are fully in view.
+    pub(crate) shown: usize,
+    /// largest offset the layout keeps, the start of the rows that fill the list at its end.
+    pub(crate) max_offset: usize,
+    /// the last start of a [`RowHeight::Measured`] list, with the length, viewport and gap it
+    /// was measured for.
+    last_start: Option<((usize, U16Vec2, u16), usize)>,
+}
+
+impl ElementCtx {
+    pub(crate) fn layout_virtual_lists(&mut self) {
+        let lists: Vec<_> = self
+            .world
+            .query_mut::<(Entity, &VirtualList, &Props, &Padding, &Gap)>()
+            .into_iter()
+            .map(|(entity, list, &props, &padding, &gap)| {
+                (entity, list.clone(), props, padding, gap)
+            })
+            .collect();
+        for (entity, list, props, padding, gap) in lists {
+            self.layout_virtual_list(entity, &list, props, padding, *gap);
+        }
+    }
+
+    fn layout_virtual_list(
+        &mut self,
+        entity: Element,
+        list: &VirtualList,
+        props: Props,
+        padding: Padding,
+        gap: u16,
+    ) {
+        let mut cache = self
+            .world
+            .remove_one::<VirtualRows>(entity)
+            .unwrap_or_default();
+        let offset = self
+            .world
+            .get::<&VirtualListState>(entity)
+            .map_or(0, |state| state.offset)
+            .min(list.len.saturating_sub(1));
+        let origin = props.position + u16vec2(padding.left, padding.top);
+        let inner_size = props.inner_size_from_padding(&padding);
+        let style = self
+            .world
+            .get::<&RowStyle>(entity)
+            .ok()
+            .map(|style| style.0);
+
+        let mut row_height = |ctx: &mut Self, idx: usize| -> u16 {
+            let row = *cache.rows.entry(idx).or_insert_with(|| {
+                let row = ctx.spawn_ui((list.row)(idx));
+                if let Some(style) = style {
+                    ctx.style_row(row, style);
+                }
+                // the theme is applied before the layout, so rows spawned during it catch up
+                let mut tree = HashSet::new();
+                collect_tree(&ctx.world, row, &mut tree);
+                ctx.apply_theme_where(|element| tree.contains(&element));
+                row
+            });
+            ctx.measure_row(row, inner_size.x, list.row_height)
+        };
+
+        let (start, end, heights) = match list.row_height {
+            // only the rows in view are built
+            RowHeight::Fixed(height) => {
+                let rows = fixed_rows(list.len, offset, height.max(1), gap, inner_size.y);
+                let heights: Vec<_> = rows.clone().map(|idx| row_height(self, idx)).collect();
+                (rows.start, rows.end, heights)
+            }
+            // fill the viewport from the offset, then pull in earlier rows if the end was reached
+            RowHeight::Measured => {
+                let mut heights = VecDeque::new();
+                // where the next row starts
+                let mut next: u16 = 0;
+                let mut end = offset;
+                while end < list.len && next < inner_size.y {
+                    let height = row_height(self, end);
+                    heights.push_back(height);
+                    next = next.saturating_add(height).saturating_add(gap);
+                    end += 1;
+                }
+                // cells taken by the rows and the gaps between them
+                let mut used = next.saturating_sub(gap);
+                let mut start = offset;
+                while end == list.len && start > 0 {
+                    let height = row_height(self, start - 1);
+                    let gap = if heights.is_empty() { 0 } else { gap };
+                    let taken = used.saturating_add(height).saturating_add(gap);
+                    if taken > inner_size.y {
+                        break;
+                    }
+                    heights.push_front(height);
+                    used = taken;
+                    start -= 1;
+                }
+                (start, end, heights.into())
+            }
+        };
+        let reached_end = end == list.len;
+        // cells taken by the rows and the gaps between them
+        let used = heights.iter().fold(0, |used: u16, &height| {
+            used.saturating_add(height).saturating_add(gap)
+        });
+        let fits = used.saturating_sub(gap) <= inner_size.y;
+        let max_offset = match list.row_height {
+            RowHeight::Fixed(height) => {
+                let fully_shown = fully_shown(height.max(1), gap, inner_size.y);
+                list.len.saturating_sub(fully_shown.max(1))
+            }
+            RowHeight::Measured if reached_end && fits => start,
+            RowHeight::Measured => {
+                let key = (list.len, inner_size, gap);
+                match cache.last_start {
+                    Some((measured, last)) if measured == key => last,
+                    _ => {
+                        // pull rows in from the end like the layout does for the last offset
+                        let mut used: u16 = 0;
+                        let mut last = list.len;
+                        while last > 0 {
+                            let height = row_height(self, last - 1);
+                            let gap = if last == list.len { 0 } else { gap };
+                            let taken = used.saturating_add(height).saturating_add(gap);
+                            if taken > inner_size.y {
+                                break;
+                            }
+                            used = taken;
+                            last -= 1;
+                        }
+                        let last = last.min(list.len.saturating_sub(1));
+                        cache.last_start = Some((key, last));
+                        last
+                    }
+                }
+            }
+        };
+
+        cache.rows.retain(|&idx, &mut row| {
+            let keep = (start..end).contains(&idx);
+            if !keep {
+                self.despawn_ui(row);
+            }
+            keep
+        });
+
+        let mut y: u16 = 0;
+        let mut shown = 0;
+        let mut children = Vec::with_capacity(heights.len());
+        for (idx, height) in (start..end).zip(heights) {
+            let row = cache.rows[&idx];
+            let visible_height = height.min(inner_size.y.saturating_sub(y));
+            if visible_height == height {
+                shown += 1;
+            }
+            if let Ok(mut row_props) = self.world.get::<&mut Props>(row) {
+                row_props.size.y = row_props.size.y.min(visible_height);
+            }
+            self.place_row(row, origin + u16vec2(0, y));
+            children.push(row);
+            y = y.saturating_add(height).saturating_add(gap);
+        }
+
+        cache.visible = start..end;
+        cache.shown = shown;
+        cache.max_offset = max_offset;
+        _ = self.world.insert(
+            entity,
+            (
+                cache,
+                VirtualListState::new(start),
+                Children::Some(Arc::new(children)),
+            ),
+        );
+    }
+
+    /// sizes a row to `width` and returns its height.
+    fn measure_row(&mut self, row: Element, width: u16, row_height: RowHeight) -> u16 {
+        let (width_grows, height_grows) = self
+            .world
+            .query_one::<(&Width, &Height)>(row)
+            .get()
+            .map_or((false, false), |(width, height)| {
+                (width.is_grow(), height.is_grow())
+            });
+        _ = self.calculate_fit_sizes(row, Direction::Horizontal);
+        if let Ok(mut props) = self.world.get::<&mut Props>(row) {
+            if width_grows {
+                props.size.x = width;
+            }
+            props.size.x = props.size.x.min(width);
+        }
+        _ = self.calculate_grow_sizes(row, false, Rect::default(), Direction::Horizontal);
+        _ = self.calculate_fit_sizes(row, Direction::Vertical);
+        let Ok(mut props) = self.world.get::<&mut Props>(row) else {
+            return 0;
+        };
+        match row_height {
+            RowHeight::Fixed(height) if height_grows => props.size.y = height,
+            RowHeight::Fixed(height) => props.size.y = props.size.y.min(height),
+            // rows must be at least a cell high for the list to make progress
+            RowHeight::Measured => props.size.y = props.size.y.max(1),
+        }
+        let height = match row_height {
+            RowHeight::Fixed(height) => height.max(1),
+            RowHeight::Measured => props.size.y,
+        };
+        drop(props);
+        _ = self.calculate_grow_sizes(row, false, Rect::default(), Direction::Vertical);
+        height
+    }
+
+    /// patches `style` onto the widget of a row, under the style of the row.
+    fn style_row(&mut self, row: Element, style: Style) {
+        let Ok(props) = self.world.get::<&Props>(row).map(|props| *props) else {
+            return;
+        };
+        let own = (props.get_style)(&self.world, row).unwrap_or_default();
+        (props.set_style)(&mut self.world, row, style.patch(own));
+    }
+
+    fn place_row(&mut self, row: Element, position: U16Vec2) {
+        if let Ok(mut props) = self.world.get::<&mut Props>(row) {
+            props.position = position;
+        }
+        _ = self.calculate_positions(row);
+    }
+
+    /// despawns the cached rows of a [`VirtualList`], so they are built again on the next
+    /// layout. use this when the data behind the rows changes.
+    pub fn refresh_virtual_list(&mut self, list: Element) {
+        let Ok(cache) = self.world.remove_one::<VirtualRows>(list) else {
+            return;
+        };
+        for row in cache.rows.into_values() {
+            self.despawn_ui(row);
+        }
+        _ = self.world.insert_one(list, Children::None);
+    }
+}
+
+/// the rows from `offset` that are at least partially in a viewport `viewport` cells high, when
+/// every row is `height` cells high. if the last row is in view, earlier rows are pulled in to
+/// fill the viewport.
+fn fixed_rows(len: usize, offset: usize, height: u16, gap: u16, viewport: u16) -> Range<usize> {
+    let step = usize::from(height) + usize::from(gap);
+    // row `n` starts at `n * step`
+    let end = offset
+        .saturating_add(usize::from(viewport).div_ceil(step))
+        .min(len);
+    if end < len {
+        return offset..end;
+    }
+    offset.min(len.saturating_sub(fully_shown(height, gap, viewport)))..end
+}
+
+/// the number of rows `height` cells high that fit in a viewport `viewport` cells high.
+fn fully_shown(height: u16, gap: u16, viewport: u16) -> usize {
+    let step = usize::from(height) + usize::from(gap);
+    usize::from(viewport)
+        .checked_sub(usize::from(height))
+        .map_or(0, |rest| rest / step + 1)
+}
+
+fn collect_tree(world: &hecs::World, element: Element, tree: &mut HashSet<Element>) {
+    tree.insert(element);
+    if let Ok(children) = world.get::<&Children>(element) {
+        for child in children.iter() {
+            collect_tree(world, child, tree);
+        }
+    }
+}
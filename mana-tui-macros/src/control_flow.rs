@@ -0,0 +1,253 @@
+//! control flow blocks in `ui!` markup.
+//!
+//! ```text
+//! {#if cond { <A/> } else if other { <B/> } else { <C/> }}
+//! {#for item in iter { <Row .item={item}/> }}
+//! {#match value { Pat => <A/>, Other if guard => { <B/> <C/> } }}
+//! ```
+//!
+//! the blocks work like their rust counterparts, with markup in place of statements. they expand
+//! to any number of children, so an element that has one among its children pushes them into its
+//! children vec as the blocks run.
+
+use proc_macro2::{Delimiter, Span, TokenStream};
+use quote::quote;
+use syn::{
+    Token,
+    parse::{Parse, ParseStream},
+};
+
+use crate::manasx::ManaElement;
+
+#[derive(Debug, Clone)]
+pub struct ControlFlow {
+    brace: syn::token::Brace,
+    block: Block,
+}
+
+#[derive(Debug, Clone)]
+enum Block {
+    If(IfBlock),
+    For(ForBlock),
+    Match(MatchBlock),
+}
+
+#[derive(Debug, Clone)]
+struct IfBlock {
+    cond: syn::Expr,
+    then: Body,
+    otherwise: Option<Else>,
+}
+
+#[derive(Debug, Clone)]
+enum Else {
+    If(Box<IfBlock>),
+    Body(Body),
+}
+
+#[derive(Debug, Clone)]
+struct ForBlock {
+    pat: syn::Pat,
+    iter: syn::Expr,
+    body: Body,
+}
+
+#[derive(Debug, Clone)]
+struct MatchBlock {
+    expr: syn::Expr,
+    arms: Vec<MatchArm>,
+}
+
+#[derive(Debug, Clone)]
+struct MatchArm {
+    pat: syn::Pat,
+    guard: Option<syn::Expr>,
+    body: Body,
+}
+
+/// the markup between the braces of a block.
+#[derive(Debug, Clone)]
+struct Body(Vec<ManaElement>);
+
+impl ControlFlow {
+    /// whether `input` starts with a control flow block, i.e. `{#`.
+    pub fn peek(input: ParseStream) -> bool {
+        input
+            .cursor()
+            .group(Delimiter::Brace)
+            .and_then(|(inside, _, _)| inside.punct())
+            .is_some_and(|(punct, _)| punct.as_char() == '#')
+    }
+
+    pub fn span(&self) -> Span {
+        self.brace.span.join()
+    }
+}
+
+impl Parse for ControlFlow {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        let brace = syn::braced!(content in input);
+        content.parse::<Token![#]>()?;
+        let lookahead = content.lookahead1();
+        let block = if lookahead.peek(Token![if]) {
+            Block::If(content.parse()?)
+        } else if lookahead.peek(Token![for]) {
+            Block::For(content.parse()?)
+        } else if lookahead.peek(Token![match]) {
+            Block::Match(content.parse()?)
+        } else {
+            return Err(lookahead.error());
+        };
+        if !content.is_empty() {
+            return Err(content.error("unexpected tokens after the block"));
+        }
+        Ok(Self { brace, block })
+    }
+}
+
+impl Parse for IfBlock {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![if]>()?;
+        let cond = syn::Expr::parse_without_eager_brace(input)?;
+        let then = input.parse()?;
+        let otherwise = match input.parse::<Option<Token![else]>>()? {
+            Some(_) if input.peek(Token![if]) => Some(Else::If(Box::new(input.parse()?))),
+            Some(_) => Some(Else::Body(input.parse()?)),
+            None => None,
+        };
+        Ok(Self {
+            cond,
+            then,
+            otherwise,
+        })
+    }
+}
+
+impl Parse for ForBlock {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![for]>()?;
+        let pat = syn::Pat::parse_multi_with_leading_vert(input)?;
+        input.parse::<Token![in]>()?;
+        let iter = syn::Expr::parse_without_eager_brace(input)?;
+        let body = input.parse()?;
+        Ok(Self { pat, iter, body })
+    }
+}
+
+impl Parse for MatchBlock {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![match]>()?;
+        let expr = syn::Expr::parse_without_eager_brace(input)?;
+        let content;
+        syn::braced!(content in input);
+        let mut arms = Vec::new();
+        while !content.is_empty() {
+            arms.push(content.parse()?);
+        }
+        Ok(Self { expr, arms })
+    }
+}
+
+impl Parse for MatchArm {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let pat = syn::Pat::parse_multi_with_leading_vert(input)?;
+        let guard = match input.parse::<Option<Token![if]>>()? {
+            Some(_) => Some(input.parse()?),
+            None => None,
+        };
+        input.parse::<Token![=>]>()?;
+        // `{ ... }` holds several children, anything else is a single one
+        let body = if input.peek(syn::token::Brace) && !ControlFlow::peek(input) {
+            input.parse()?
+        } else {
+            Body(vec![input.parse()?])
+        };
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self { pat, guard, body })
+    }
+}
+
+impl Parse for Body {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        syn::braced!(content in input);
+        let mut children = Vec::new();
+        while !content.is_empty() {
+            children.push(content.parse()?);
+        }
+        Ok(Self(children))
+    }
+}
+
+/// statements that push `elements` onto the vec named `children`.
+pub fn push_children(elements: &[ManaElement], children: &syn::Ident) -> TokenStream {
+    elements
+        .iter()
+        .map(|element| match element {
+            ManaElement::ControlFlow(flow) => flow.block.push_into(children),
+            element => quote! { #children.push(#element); },
+        })
+        .collect()
+}
+
+impl Block {
+    fn push_into(&self, children: &syn::Ident) -> TokenStream {
+        match self {
+            Block::If(block) => block.push_into(children),
+            Block::For(ForBlock { pat, iter, body }) => {
+                let body = body.push_into(children);
+                quote! {
+                    for #pat in #iter {
+                        #body
+                    }
+                }
+            }
+            Block::Match(MatchBlock { expr, arms }) => {
+                let arms = arms.iter().map(|MatchArm { pat, guard, body }| {
+                    let guard = guard.as_ref().map(|guard| quote! { if #guard });
+                    let body = body.push_into(children);
+                    quote! { #pat #guard => { #body } }
+                });
+                quote! {
+                    match #expr {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl IfBlock {
+    fn push_into(&self, children: &syn::Ident) -> TokenStream {
+        let IfBlock {
+            cond,
+            then,
+            otherwise,
+        } = self;
+        let then = then.push_into(children);
+        let otherwise = match otherwise {
+            Some(Else::If(block)) => {
+                let block = block.push_into(children);
+                quote! { else #block }
+            }
+            Some(Else::Body(body)) => {
+                let body = body.push_into(children);
+                quote! { else { #body } }
+            }
+            None => quote! {},
+        };
+        quote! {
+            if #cond {
+                #then
+            } #otherwise
+        }
+    }
+}
+
+impl Body {
+    fn push_into(&self, children: &syn::Ident) -> TokenStream {
+        push_children(&self.0, children)
+    }
+}
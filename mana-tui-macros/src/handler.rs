@@ -0,0 +1,348 @@
+//! event handler attributes in `ui!` markup.
+//!
+//! ```text
+//! <Block @click={|world| ..} @key:ctrl+s={|world| ..} @hover={|world| ..}>
+//! ```
+//!
+//! handlers expand to the components of `mana_tui_beheaded::focus`, so they take a
+//! `Fn(&mut World)`. potion dispatches events to its own `On` components instead, so handlers
+//! are rejected in crates that use it. key specs are checked here, so a typo is a compile error
+//! instead of a keybind that never fires.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    Token,
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+};
+
+use crate::utils::{mana_tui_beheaded, uses_potion};
+
+#[derive(Debug, Clone)]
+pub struct Handler {
+    event: Event,
+    value: syn::ExprBlock,
+    /// whether `@click` and `@hover` make the element block focus. unset when the element has
+    /// its own `FocusPolicy`.
+    block_focus: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Event {
+    Click(Span),
+    Key(KeySpec),
+    Hover(Span),
+}
+
+#[derive(Debug, Clone)]
+struct KeySpec {
+    span: Span,
+    modifiers: Vec<Modifier>,
+    code: TokenStream,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Modifier {
+    Control,
+    Shift,
+    Alt,
+    Super,
+    Hyper,
+    Meta,
+}
+
+const EVENTS: &str = "`click`, `key` or `hover`";
+
+const NAMED_KEYS: &[(&str, &str)] = &[
+    ("enter", "Enter"),
+    ("esc", "Esc"),
+    ("tab", "Tab"),
+    ("backtab", "BackTab"),
+    ("backspace", "Backspace"),
+    ("delete", "Delete"),
+    ("insert", "Insert"),
+    ("home", "Home"),
+    ("end", "End"),
+    ("pageup", "PageUp"),
+    ("pagedown", "PageDown"),
+    ("up", "Up"),
+    ("down", "Down"),
+    ("left", "Left"),
+    ("right", "Right"),
+];
+
+impl Handler {
+    /// whether `input` starts with a handler, i.e. `@`.
+    pub fn peek(input: ParseStream) -> bool {
+        input.peek(Token![@])
+    }
+
+    fn span(&self) -> Span {
+        match &self.event {
+            Event::Click(span) | Event::Hover(span) => *span,
+            Event::Key(spec) => spec.span,
+        }
+    }
+
+    /// keeps the `FocusPolicy` the element was given.
+    pub fn keep_focus_policy(&mut self) {
+        self.block_focus = false;
+    }
+
+    fn event_name(&self) -> &'static str {
+        match self.event {
+            Event::Click(_) => "click",
+            Event::Key(_) => "key",
+            Event::Hover(_) => "hover",
+        }
+    }
+}
+
+impl Parse for Handler {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![@]>()?;
+        let name = syn::Ident::parse_any(input)?;
+        let event = match name.to_string().as_str() {
+            "click" | "hover" if input.peek(Token![:]) => {
+                return Err(input.error(format!("`@{name}` does not take a key")));
+            }
+            "click" => Event::Click(name.span()),
+            "hover" => Event::Hover(name.span()),
+            "key" => {
+                if !input.peek(Token![:]) {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        "`@key` needs a key, like `@key:ctrl+s`",
+                    ));
+                }
+                input.parse::<Token![:]>()?;
+                Event::Key(input.parse()?)
+            }
+            other => {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("unknown event `{other}`, expected {EVENTS}"),
+                ));
+            }
+        };
+        if uses_potion() {
+            return Err(syn::Error::new(
+                name.span(),
+                format!(
+                    "`@{name}` needs the beheaded runtime, potion apps handle events with `On` \
+                     components"
+                ),
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        let value = input
+            .parse()
+            .map_err(|err| syn::Error::new(err.span(), "expected a handler in braces"))?;
+        Ok(Self {
+            event,
+            value,
+            block_focus: true,
+        })
+    }
+}
+
+impl Parse for KeySpec {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut parts = vec![KeyPart::parse(input)?];
+        while input.peek(Token![+]) {
+            input.parse::<Token![+]>()?;
+            parts.push(KeyPart::parse(input)?);
+        }
+        let key = parts.pop().expect("there is at least one part");
+        let mut modifiers = Vec::new();
+        for part in parts {
+            let Some(modifier) = part.modifier() else {
+                return Err(syn::Error::new(
+                    part.span,
+                    format!(
+                        "`{}` is not a modifier, expected `ctrl`, `shift`, `alt`, `super`, \
+                         `hyper` or `meta`",
+                        part.text
+                    ),
+                ));
+            };
+            if modifiers.contains(&modifier) {
+                return Err(syn::Error::new(
+                    part.span,
+                    format!("`{}` is used twice", part.text),
+                ));
+            }
+            modifiers.push(modifier);
+        }
+        if key.modifier().is_some() {
+            return Err(syn::Error::new(
+                key.span,
+                format!("missing key after `{}`", key.text),
+            ));
+        }
+        let code = key.code(modifiers.contains(&Modifier::Shift))?;
+        Ok(Self {
+            span: key.span,
+            modifiers,
+            code,
+        })
+    }
+}
+
+/// one `+` separated part of a key spec.
+struct KeyPart {
+    text: String,
+    span: Span,
+}
+
+impl KeyPart {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let lookahead = input.lookahead1();
+        let (text, span) = if lookahead.peek(syn::Ident::peek_any) {
+            let ident = syn::Ident::parse_any(input)?;
+            (ident.to_string(), ident.span())
+        } else if lookahead.peek(syn::LitInt) {
+            let lit = input.parse::<syn::LitInt>()?;
+            (lit.to_string(), lit.span())
+        } else if lookahead.peek(syn::LitChar) {
+            let lit = input.parse::<syn::LitChar>()?;
+            (lit.value().to_string(), lit.span())
+        } else {
+            return Err(lookahead.error());
+        };
+        Ok(Self { text, span })
+    }
+
+    fn modifier(&self) -> Option<Modifier> {
+        let modifier = match self.text.as_str() {
+            "ctrl" | "control" => Modifier::Control,
+            "shift" => Modifier::Shift,
+            "alt" => Modifier::Alt,
+            "super" => Modifier::Super,
+            "hyper" => Modifier::Hyper,
+            "meta" => Modifier::Meta,
+            _ => return None,
+        };
+        Some(modifier)
+    }
+
+    /// the `KeyCode` variant. terminals report shifted letters in upper case.
+    fn code(&self, shift: bool) -> syn::Result<TokenStream> {
+        let text = self.text.as_str();
+        let mut chars = text.chars();
+        if let (Some(ch), None) = (chars.next(), chars.next()) {
+            let ch = if shift { ch.to_ascii_uppercase() } else { ch };
+            return Ok(quote! { Char(#ch) });
+        }
+        if text == "space" {
+            return Ok(quote! { Char(' ') });
+        }
+        if let Some(&(_, variant)) = NAMED_KEYS.iter().find(|(name, _)| *name == text) {
+            let variant = syn::Ident::new(variant, self.span);
+            return Ok(quote! { #variant });
+        }
+        if let Some(num) = text
+            .strip_prefix('f')
+            .and_then(|num| num.parse::<u8>().ok())
+            .filter(|num| (1..=12).contains(num))
+        {
+            return Ok(quote! { F(#num) });
+        }
+        Err(syn::Error::new(
+            self.span,
+            format!(
+                "unknown key `{text}`, expected a single character, `space`, `f1` to `f12` or \
+                 one of {}",
+                NAMED_KEYS
+                    .iter()
+                    .map(|(name, _)| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ))
+    }
+}
+
+impl Modifier {
+    fn name(self) -> &'static str {
+        match self {
+            Modifier::Control => "CONTROL",
+            Modifier::Shift => "SHIFT",
+            Modifier::Alt => "ALT",
+            Modifier::Super => "SUPER",
+            Modifier::Hyper => "HYPER",
+            Modifier::Meta => "META",
+        }
+    }
+}
+
+/// checks that no event has two handlers on the same element, since each one is a single
+/// component.
+pub fn check_unique(handlers: &[Handler]) -> syn::Result<()> {
+    for (idx, handler) in handlers.iter().enumerate() {
+        if handlers[..idx]
+            .iter()
+            .any(|other| other.event_name() == handler.event_name())
+        {
+            return Err(syn::Error::new(
+                handler.span(),
+                format!(
+                    "this element already has a `@{}` handler",
+                    handler.event_name()
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+impl quote::ToTokens for Handler {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let beheaded = mana_tui_beheaded();
+        let focus = quote! { #beheaded::focus };
+        let Handler {
+            event,
+            value,
+            block_focus,
+        } = self;
+        let span = value.span();
+        // `{ |world| .. }` would trip `unused_braces`
+        let value = if let [syn::Stmt::Expr(expr, None)] = value.block.stmts.as_slice() {
+            quote! { #expr }
+        } else {
+            quote! { #value }
+        };
+        // only elements that block focus receive mouse events
+        let policy = block_focus.then(|| quote! { #focus::FocusPolicy::Block, });
+        let tok = match event {
+            Event::Click(_) => quote_spanned! { span =>
+                .with((#policy #focus::OnClick::new(#value),))
+            },
+            Event::Hover(_) => quote_spanned! { span =>
+                .with((#policy #focus::OnHover::new(#value),))
+            },
+            Event::Key(KeySpec {
+                modifiers, code, ..
+            }) => {
+                let modifiers = (!modifiers.is_empty()).then(|| {
+                    let names = modifiers
+                        .iter()
+                        .map(|modifier| syn::Ident::new(modifier.name(), Span::call_site()));
+                    quote! {
+                        .modifiers(#(::ratatui::crossterm::event::KeyModifiers::#names)|*)
+                    }
+                });
+                quote_spanned! { span =>
+                    .with((
+                        #focus::Keybind::new(::ratatui::crossterm::event::KeyCode::#code)
+                            #modifiers,
+                        #focus::OnPress::new(#value),
+                    ))
+                }
+            }
+        };
+        tokens.extend(tok);
+    }
+}
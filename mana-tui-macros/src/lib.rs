@@ -1,7 +1,12 @@
 use quote::quote;
 use syn::parse_macro_input;
 
+mod control_flow;
+mod handler;
 mod manasx;
+mod markup;
+mod props;
+mod slot;
 mod subview;
 mod utils;
 
@@ -21,11 +26,141 @@ use crate::utils::mana_tui_elemental;
 ///    </Block>
 /// };
 ///```
+///
+/// # Markup
+///
+/// `<Text>`, `<Paragraph>`, `<Line>` and `<Span>` with the `.markup` attribute can style parts of
+/// their literal with inline tags, which are compiled into styled spans and lines. `[[` is a
+/// literal `[`. other literals keep their brackets as text.
+///
+///```
+/// use mana_tui_macros::ui;
+/// use mana_tui::prelude::*;
+///
+/// let count = 3;
+/// let root = ui! {
+///    <Block>
+///        <Text .markup>"[b]bold[/b] [fg=red]{count}[/fg] [bg=#303030][i]unclosed tags"</Text>
+///        <Line .markup>"[u]underlined[/u] [dim]dim[/]"</Line>
+///        "[b] is plain text here"
+///    </Block>
+/// };
+///```
+///
+/// supported tags are `b`, `i`, `u`, `s` (crossed out), `dim`, `rev`, `blink`, `fg=color` and
+/// `bg=color`. they are closed with `[/name]` or `[/]`. brackets that don't hold a known tag are
+/// kept as text.
+///
+/// # Control flow
+///
+/// `{#if}`, `{#for}` and `{#match}` blocks add children conditionally or in a loop. their bodies
+/// hold markup instead of statements, and a match arm is either one element or a braced list.
+///
+///```
+/// use mana_tui_macros::ui;
+/// use mana_tui::prelude::*;
+///
+/// let done = true;
+/// let items = ["milk", "eggs"];
+/// let selected = Some(1);
+/// let root = ui! {
+///    <Block>
+///        {#if done {
+///            <Text>"[x]"</Text>
+///        } else {
+///            <Text>"[ ]"</Text>
+///        }}
+///        {#for item in items {
+///            "- {item}"
+///        }}
+///        {#match selected {
+///            Some(idx) if idx < items.len() => "selected {idx}",
+///            _ => {
+///                "nothing selected"
+///                <Block .title_top="empty" />
+///            }
+///        }}
+///    </Block>
+/// };
+///```
+///
+/// blocks can only appear among the children of an element.
+///
+/// # Event handlers
+///
+/// `@click`, `@hover` and `@key:<keys>` attach the handlers of `mana_tui_beheaded::focus` to an
+/// element. they take a `Fn(&mut World)` and can be mixed with components.
+///
+///```
+/// use mana_tui_macros::ui;
+/// use mana_tui::prelude::*;
+///
+/// let root = ui! {
+///    <Block
+///        @click={|world| _ = world.spawn(("clicked",))}
+///        @key:ctrl+s={|world| _ = world.spawn(("saved",))}
+///        @hover={|_| {}}
+///        Width::grow()
+///    />
+/// };
+///```
+///
+/// a key is a single character, `space`, `f1` to `f12`, or one of `enter`, `esc`, `tab`,
+/// `backtab`, `backspace`, `delete`, `insert`, `home`, `end`, `pageup`, `pagedown`, `up`, `down`,
+/// `left` and `right`, after any of the modifiers `ctrl`, `shift`, `alt`, `super`, `hyper` and
+/// `meta`. unknown keys are a compile error.
+///
+/// `@click` and `@hover` also make the element block focus, since only those receive mouse
+/// events, unless it is given a `FocusPolicy` component. like any keybind, pressing the key of
+/// `@key` clicks the element, so it runs the `@click` handler before its own.
+///
+/// handlers need the beheaded runtime. in crates that use `mana-tui-potion` they are a compile
+/// error, since potion handles events with its own `On` components.
+///
+/// # Subviews
+///
+/// markup nested in a subview tag is passed to its `children` parameter. `<Name:slot>` tags pass
+/// their markup to the parameter named `slot` instead, so a subview can take several lists of
+/// children. nesting markup in a subview without a `children` parameter is a compile error.
+///
+///```
+/// use mana_tui_macros::{subview, ui};
+/// use mana_tui::prelude::*;
+///
+/// #[subview]
+/// fn card(header: impl AsChildren, children: impl AsChildren) -> View {
+///     ui! {
+///        <Block .rounded>
+///            <Block Direction::Horizontal>{header}</Block>
+///            <Block>{children}</Block>
+///        </Block>
+///     }
+/// }
+///
+/// let root = ui! {
+///    <Card>
+///        <Card:header>"title"</Card:header>
+///        "body"
+///    </Card>
+/// };
+///```
+///
+/// slots must be direct children of their subview, and `<Name:slot />` passes no children.
+///
+/// `..{props}` sets every prop of a subview from its props struct, see [`macro@subview`]. spreads go
+/// with the attributes, which can't set a prop the spread already sets.
 #[proc_macro]
 pub fn ui(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     // let input = preprocess_tokens(input.into());
     // let input = input.into();
-    let tree = parse_macro_input!(input as ManaElement);
+    let tree = match syn::parse::<ManaElement>(input) {
+        Ok(tree) => tree,
+        // the macro is an expression, and one error can hold several `compile_error!`s
+        Err(err) => {
+            let errors = err.to_compile_error();
+            return quote! { { #errors } }.into();
+        }
+    };
     let tree = quote! { #tree };
     let mana_crate = mana_tui_elemental();
     let tokens = quote! {
@@ -38,6 +173,41 @@ pub fn ui(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     tokens.into()
 }
 
+/// turns a function that returns a `View` into a subview, which `ui!` can use as a tag.
+///
+/// the parameters are set with the attributes of the tag. `#[prop(default)]` and
+/// `#[prop(default = expr)]` make one optional, and `#[prop(into)]` makes it take anything that
+/// converts into its type.
+///
+/// the parameters with a default also go into a `{Name}Props` struct, so a set of them can be
+/// passed around and spread into the tag with `..{props}`.
+///
+///```
+/// use mana_tui_macros::{subview, ui};
+/// use mana_tui::prelude::*;
+///
+/// #[subview]
+/// fn button(
+///     label: &str,
+///     #[prop(default = 1)] padding: u16,
+///     #[prop(into, default = "button")] title: String,
+/// ) -> View {
+///     ui! {
+///        <Block .title_top={title} Padding::uniform(padding)>"{label}"</Block>
+///     }
+/// }
+///
+/// let wide = ButtonProps {
+///     padding: 2,
+///     ..Default::default()
+/// };
+/// let root = ui! {
+///    <Block>
+///        <Button .label="ok" />
+///        <Button ..{wide} .label="cancel" .title="danger" />
+///    </Block>
+/// };
+///```
 #[proc_macro_attribute]
 pub fn subview(
     args: proc_macro::TokenStream,
@@ -1,15 +1,28 @@
 use std::fmt::Display;
 
 use proc_macro2::{Span, TokenStream};
-use quote::{quote, quote_spanned};
+use quote::{format_ident, quote, quote_spanned};
 use syn::{
     Token, parenthesized,
     parse::{Parse, discouraged::Speculative},
     spanned::Spanned,
 };
 
+use crate::control_flow::{self, ControlFlow};
+use crate::handler::{self, Handler};
+use crate::markup::Markup;
+use crate::slot::{self, Slot};
+use crate::utils::mana_tui_elemental;
+
 macro_rules! impl_parse_enum {
 ($enum_name:ident { $($variant:ident($inner:ty)),* $(,)? }) => {
+    impl_parse_enum!(
+        $enum_name,
+        concat!("expected one of: ", $(stringify!($variant), ", "),*),
+        { $($variant($inner)),* }
+    );
+};
+($enum_name:ident, $msg:expr, { $($variant:ident($inner:ty)),* $(,)? }) => {
     impl syn::parse::Parse for $enum_name {
         fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
             $(
@@ -21,7 +34,7 @@ macro_rules! impl_parse_enum {
                 }
             )*
 
-            Err(input.error(concat!("expected one of: ", $(stringify!($variant), ", "),*)))
+            Err(input.error($msg))
         }
     }
 };
@@ -101,7 +114,7 @@ impl ManaName {
     }
 }
 
-impl_parse_enum!(ManaName {
+impl_parse_enum!(ManaName, "expected a tag name", {
     Ident(syn::Ident),
     Path(syn::ExprPath),
 });
@@ -110,6 +123,8 @@ impl_parse_enum!(ManaName {
 struct ManaTagData {
     ident: ManaName,
     attrs: ManaAttrVec,
+    spreads: Vec<PropsSpread>,
+    handlers: Vec<Handler>,
     components: ComponentVec,
 }
 
@@ -149,7 +164,9 @@ impl_quote_enum!(ManaAttrValue {
     ExprBlock
 });
 impl_parse_enum!(
-    ManaAttrValue {
+    ManaAttrValue,
+    "expected a literal, a tuple or a `{...}` block",
+    {
         Lit(syn::Lit),
         ExprTuple(syn::ExprTuple),
         ExprBlock(syn::ExprBlock),
@@ -159,6 +176,13 @@ impl_parse_enum!(
 #[derive(Debug, Clone)]
 struct ManaAttrVec(Vec<ManaAttr>);
 
+/// `..{props}`, which sets every prop of a subview at once.
+#[derive(Debug, Clone)]
+struct PropsSpread {
+    _dots: Token![..],
+    value: syn::ExprBlock,
+}
+
 #[derive(Debug, Clone)]
 struct Component(ComponentExpr);
 
@@ -186,7 +210,7 @@ enum ComponentExpr {
     Unsafe(syn::ExprUnsafe),
 }
 
-impl_parse_enum!(ComponentExpr {
+impl_parse_enum!(ComponentExpr, "expected a component, `/>` or `>`", {
     Call(syn::ExprCall),
     PathCall(PathCall),
     Block(syn::ExprBlock),
@@ -256,46 +280,92 @@ impl Parse for PathCall {
     }
 }
 
-#[derive(Debug, Clone)]
-struct ComponentVec(Vec<Component>);
-
-impl Parse for ComponentVec {
-    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let components = parse_any::<Component>(input).collect::<Vec<_>>();
-        Ok(Self(components))
+impl Component {
+    /// whether this is a variant of `FocusPolicy`, like `FocusPolicy::Pass`.
+    fn is_focus_policy(&self) -> bool {
+        matches!(&self.0, ComponentExpr::Path(path)
+            if path.path.segments.iter().any(|segment| segment.ident == "FocusPolicy"))
     }
 }
 
+#[derive(Debug, Clone)]
+struct ComponentVec(Vec<Component>);
+
 #[derive(Debug, Clone)]
 struct Element {
     open: OpenTag,
     children: Children,
+    slots: Vec<Slot>,
     close: CloseTag,
 }
 
+/// tags that hold a string literal instead of children.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextTag {
+    Text,
+    Paragraph,
+    Line,
+    Span,
+    Markdown,
+}
+
+const TEXT_TAGS: [(&str, TextTag); 5] = [
+    ("Text", TextTag::Text),
+    ("Paragraph", TextTag::Paragraph),
+    ("Line", TextTag::Line),
+    ("Span", TextTag::Span),
+    ("Markdown", TextTag::Markdown),
+];
+
+impl TextTag {
+    fn from_name(name: &str) -> Option<Self> {
+        TEXT_TAGS
+            .iter()
+            .find(|(tag, _)| *tag == name)
+            .map(|&(_, tag)| tag)
+    }
+
+    /// the function that builds the widget from the formatted literal.
+    fn constructor(self) -> TokenStream {
+        match self {
+            Self::Text | Self::Line | Self::Span => quote! { raw },
+            Self::Paragraph | Self::Markdown => quote! { new },
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct TextElement {
     open: OpenTag,
+    tag: TextTag,
     text: syn::LitStr,
+    /// whether `.markup` was given, so the text is parsed as markup.
+    markup: bool,
     close: CloseTag,
 }
 
 #[derive(Debug, Clone)]
-enum Children {
+pub enum Children {
     Block(ChildrenBlock),
     List(ChildrenList),
 }
 
+impl Children {
+    pub fn empty() -> Self {
+        Self::List(ChildrenList(Vec::new()))
+    }
+
+    fn is_empty(&self) -> bool {
+        matches!(self, Self::List(list) if list.0.is_empty())
+    }
+}
+
 #[derive(Debug, Clone)]
-struct ChildrenBlock(BraceBlock);
+pub struct ChildrenBlock(BraceBlock);
 
 #[derive(Debug, Clone)]
-struct ChildrenList(Vec<ManaElement>);
+pub struct ChildrenList(Vec<ManaElement>);
 
-impl_parse_enum!(Children {
-    Block(ChildrenBlock),
-    List(ChildrenList),
-});
 impl_quote_enum!(Children { Block, List });
 
 #[derive(Debug, Clone)]
@@ -311,73 +381,183 @@ pub enum ManaElement {
     Element(Box<Element>),
     TextElement(TextElement),
     SelfClosing(OpenTag),
+    ControlFlow(ControlFlow),
+    Slot(Slot),
 }
 
 impl Parse for ManaElement {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let f = input.fork();
-        let exprblock = f.parse::<BraceBlock>();
-        if let Ok(exprblock) = exprblock {
-            input.advance_to(&f);
-            return Ok(Self::ExprBlock(exprblock));
+        if ControlFlow::peek(input) {
+            return input.parse().map(Self::ControlFlow);
         }
-
-        let f = input.fork();
-        let litstr = f.parse::<syn::LitStr>();
-        if let Ok(litstr) = litstr {
-            input.advance_to(&f);
-            return Ok(Self::Plaintext(litstr));
+        if input.peek(syn::token::Brace) {
+            return input.parse().map(Self::ExprBlock);
+        }
+        if input.peek(syn::LitStr) {
+            return input.parse().map(Self::Plaintext);
+        }
+        if !input.peek(Token![<]) {
+            return Err(input.error("expected an element, a string literal or a `{...}` block"));
+        }
+        if Slot::peek(input) {
+            return input.parse().map(Self::Slot);
+        }
+        if input.peek2(Token![/]) {
+            let close = input.parse::<CloseTag>()?;
+            return Err(syn::Error::new(
+                close.ident.span(),
+                format!("closing tag </{}> has no opening tag", close.ident),
+            ));
         }
 
-        let f = input.fork();
-        let open = f.parse::<OpenTag>()?;
-        input.advance_to(&f);
+        let mut open = input.parse::<OpenTag>()?;
         if open.sl.is_some() {
             return Ok(Self::SelfClosing(open));
         }
 
-        match open.data.name()?.as_str() {
-            "Text" | "Paragraph" | "Span" | "Line" => {
-                let text = input.parse()?;
-                let close = input.parse::<CloseTag>()?;
-                if close.ident != open.data.ident {
-                    return Err(syn::Error::new(
-                        close.ident.span(),
-                        format!(
-                            "closing tag </{}> does not match opening <{}>",
-                            close.ident, open.data.ident
-                        ),
-                    ));
-                }
-                return Ok(Self::TextElement(TextElement { open, text, close }));
+        if let Some(tag) = TextTag::from_name(&open.data.name()?) {
+            if let Some(spread) = open.data.spreads.first() {
+                return Err(syn::Error::new(
+                    spread.value.span(),
+                    format!("<{}> does not take props", open.data.ident),
+                ));
+            }
+            if is_closing(input) {
+                return Err(syn::Error::new(
+                    open.span(),
+                    format!("<{}> needs a string literal", open.data.ident),
+                ));
+            }
+            if !input.peek(syn::LitStr) {
+                return Err(input.error(format!(
+                    "<{}> can only hold a string literal, use a <Block> to hold other elements",
+                    open.data.ident
+                )));
             }
-            _ => {}
+            let markup = take_markup_attr(&mut open.data, tag)?;
+            let text = input.parse()?;
+            let close = parse_close(input, &open)?;
+            return Ok(Self::TextElement(TextElement {
+                open,
+                tag,
+                text,
+                markup,
+                close,
+            }));
         }
 
-        let children = input.parse()?;
-        let close = input.parse::<CloseTag>()?;
-        if close.ident != open.data.ident {
-            return Err(syn::Error::new(
-                close.ident.span(),
-                format!(
-                    "closing tag </{}> does not match opening <{}>",
-                    close.ident, open.data.ident
-                ),
-            ));
-        }
+        let mut children = input.parse()?;
+        let close = parse_close(input, &open)?;
+        let slots = take_slots(&mut children, &open)?;
         Ok(Self::Element(Box::new(Element {
             open,
             children,
+            slots,
             close,
         })))
     }
 }
 
+/// removes the `.markup` attribute of a text tag, returning whether it was there.
+fn take_markup_attr(data: &mut ManaTagData, tag: TextTag) -> syn::Result<bool> {
+    let Some(idx) = data
+        .attrs
+        .0
+        .iter()
+        .position(|attr| attr.fn_name == "markup")
+    else {
+        return Ok(false);
+    };
+    let attr = data.attrs.0.remove(idx);
+    if let Some(assign) = attr.assign {
+        return Err(syn::Error::new(
+            assign.value.span(),
+            "`.markup` does not take a value",
+        ));
+    }
+    if tag == TextTag::Markdown {
+        return Err(syn::Error::new(
+            attr.fn_name.span(),
+            "<Markdown> has its own syntax and does not take `.markup`",
+        ));
+    }
+    Ok(true)
+}
+
+/// moves the slots out of `children`, checking that they belong to `open`.
+fn take_slots(children: &mut Children, open: &OpenTag) -> syn::Result<Vec<Slot>> {
+    let Children::List(ChildrenList(elements)) = children else {
+        return Ok(Vec::new());
+    };
+    let mut slots = Vec::new();
+    for element in std::mem::take(elements) {
+        match element {
+            ManaElement::Slot(slot) => {
+                slot.check_parent(&open.data.ident.to_string())?;
+                slots.push(slot);
+            }
+            element => elements.push(element),
+        }
+    }
+    slot::check_unique(&slots)?;
+    Ok(slots)
+}
+
+/// whether `input` is at the end of its parent's children, i.e. `</` or the end of the macro.
+fn is_closing(input: syn::parse::ParseStream) -> bool {
+    input.is_empty() || (input.peek(Token![<]) && input.peek2(Token![/]))
+}
+
+/// parses the tag that closes `open`, pointing at `open` if there is none.
+fn parse_close(input: syn::parse::ParseStream, open: &OpenTag) -> syn::Result<CloseTag> {
+    if input.is_empty() {
+        return Err(syn::Error::new(
+            open.span(),
+            format!("unclosed tag <{}>", open.data.ident),
+        ));
+    }
+    if !is_closing(input) {
+        return Err(input.error(format!("expected </{}>", open.data.ident)));
+    }
+    let close = input.parse::<CloseTag>()?;
+    if close.ident != open.data.ident {
+        let mut err = syn::Error::new(
+            close.ident.span(),
+            format!(
+                "closing tag </{}> does not match opening <{}>",
+                close.ident, open.data.ident
+            ),
+        );
+        err.combine(syn::Error::new(
+            open.span(),
+            format!("<{}> is opened here", open.data.ident),
+        ));
+        return Err(err);
+    }
+    Ok(close)
+}
+
+impl Parse for Children {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        // a lone `{...}` holds all the children, otherwise it is one of them
+        if input.peek(syn::token::Brace) && !ControlFlow::peek(input) {
+            let f = input.fork();
+            if let Ok(block) = f.parse::<ChildrenBlock>()
+                && is_closing(&f)
+            {
+                input.advance_to(&f);
+                return Ok(Self::Block(block));
+            }
+        }
+        input.parse().map(Self::List)
+    }
+}
+
 impl Parse for ChildrenList {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let mut ret = Vec::new();
-        while let Ok(child) = input.parse() {
-            ret.push(child);
+        while !is_closing(input) {
+            ret.push(input.parse()?);
         }
 
         Ok(ChildrenList(ret))
@@ -402,29 +582,71 @@ impl Parse for ChildrenBlock {
     }
 }
 
-fn parse_any<T: Parse>(input: syn::parse::ParseStream) -> impl Iterator<Item = T> {
-    std::iter::from_fn(move || input.parse::<T>().ok())
-}
-
 impl Parse for ManaTagData {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let ident = input.parse()?;
-        let attrs = input.parse()?;
-        let components = input.parse()?;
+        // spreads can be mixed with attributes
+        let mut attrs = Vec::new();
+        let mut spreads = Vec::new();
+        while input.peek(Token![.]) {
+            if input.peek(Token![..]) {
+                spreads.push(input.parse()?);
+            } else {
+                attrs.push(input.parse()?);
+            }
+        }
+        // handlers can be mixed with components
+        let mut handlers = Vec::new();
+        let mut components = Vec::new();
+        while !input.peek(Token![/]) && !input.peek(Token![>]) {
+            if Handler::peek(input) {
+                handlers.push(input.parse()?);
+            } else if input.peek(Token![..]) {
+                return Err(input.error(format!(
+                    "spreads go before the components, like <{ident} ..{{props}} Width::grow()>"
+                )));
+            } else if input.peek(Token![.]) {
+                return Err(input.error(
+                    "unexpected `.`, attributes go before the components and method calls need \
+                     braces, like `{value.method()}`",
+                ));
+            } else if input.peek(syn::LitStr) {
+                return Err(input.error(format!(
+                    "text goes between the tags, like <{ident}>\"text\"</{ident}>"
+                )));
+            } else {
+                components.push(input.parse()?);
+            }
+        }
+        handler::check_unique(&handlers)?;
+        if components.iter().any(Component::is_focus_policy) {
+            handlers.iter_mut().for_each(Handler::keep_focus_policy);
+        }
         Ok(ManaTagData {
             ident,
-            attrs,
-            components,
+            attrs: ManaAttrVec(attrs),
+            spreads,
+            handlers,
+            components: ComponentVec(components),
         })
     }
 }
 
 impl Parse for ManaAttr {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let dot = input.parse()?;
+        let fn_name = input
+            .parse()
+            .map_err(|err| syn::Error::new(err.span(), "expected a method name after `.`"))?;
+        let assign = if input.peek(Token![=]) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
         Ok(ManaAttr {
-            _dot: input.parse()?,
-            fn_name: input.parse()?,
-            assign: parse_any(input).next(),
+            _dot: dot,
+            fn_name,
+            assign,
         })
     }
 }
@@ -438,10 +660,16 @@ impl Parse for ManaAttrAssign {
     }
 }
 
-impl Parse for ManaAttrVec {
+impl Parse for PropsSpread {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let attrs = parse_any::<ManaAttr>(input).collect::<Vec<_>>();
-        Ok(Self(attrs))
+        let dots = input.parse()?;
+        if !input.peek(syn::token::Brace) {
+            return Err(input.error("expected `{...}` after `..`, like `..{props}`"));
+        }
+        Ok(Self {
+            _dots: dots,
+            value: input.parse()?,
+        })
     }
 }
 
@@ -492,13 +720,24 @@ impl quote::ToTokens for ManaElement {
             }
             ManaElement::SelfClosing(open_tag) => {
                 let span = open_tag.span();
-                tokens.extend(quote_spanned! { span => { #open_tag.done() } });
+                tokens.extend(quote_spanned! { span => #open_tag.done() });
             }
             ManaElement::ExprBlock(expr_block) => {
                 tokens.extend(quote! {
                     __ui_internal(#expr_block .into_view()).done()
                 });
             }
+            ManaElement::ControlFlow(flow) => tokens.extend(
+                syn::Error::new(flow.span(), "control flow blocks must be inside an element")
+                    .to_compile_error(),
+            ),
+            ManaElement::Slot(slot) => tokens.extend(
+                syn::Error::new(
+                    slot.span(),
+                    "slots must be direct children of their subview",
+                )
+                .to_compile_error(),
+            ),
         }
     }
 }
@@ -515,17 +754,45 @@ impl quote::ToTokens for OpenTag {
     }
 }
 
-impl quote::ToTokens for ManaTagData {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
+impl ManaTagData {
+    /// the widget or subview builder, with its props and attributes set.
+    fn widget(&self) -> TokenStream {
         let ManaTagData {
             ident,
             attrs,
+            spreads,
+            ..
+        } = self;
+        let mana_crate = mana_tui_elemental();
+        let mut widget = quote! { #ident::default() };
+        for PropsSpread { value, .. } in spreads {
+            let span = value.span();
+            let value = unbraced(value);
+            widget = quote_spanned! { span =>
+                #mana_crate::ui::Spread::spread(#widget, #value)
+            };
+        }
+        quote! { #widget #attrs }
+    }
+
+    /// the element built from `view`, with its handlers and components.
+    fn element(&self, view: &TokenStream) -> TokenStream {
+        let ManaTagData {
+            handlers,
             components,
+            ..
         } = self;
-        let out = quote! {
-            __ui_internal(#ident::default() #attrs .into_view())#components
-        };
-        tokens.extend(out);
+        // components come last, so they can override what a handler adds
+        quote! {
+            __ui_internal(#view) #(#handlers)* #components
+        }
+    }
+}
+
+impl quote::ToTokens for ManaTagData {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let widget = self.widget();
+        tokens.extend(self.element(&quote! { #widget.into_view() }));
     }
 }
 
@@ -550,15 +817,23 @@ impl quote::ToTokens for ComponentVec {
             .iter()
             .map(|component| {
                 let Component(c_expr) = component;
+                // errors about the bundle point at the component instead of the whole macro
+                let span = c_expr.span();
                 match c_expr {
                     ComponentExpr::Tuple(c_expr) => {
-                        quote! {
+                        quote_spanned! { span =>
                             .with(#c_expr)
                         }
                     }
+                    // checked on its own, so rustc names the component and not `UiBuilder::with`
                     _ => {
-                        quote! {
-                            .with((#c_expr,))
+                        quote_spanned! { span =>
+                            .with({
+                                const fn component<T: Send + Sync + 'static>(bundle: (T,)) -> (T,) {
+                                    bundle
+                                }
+                                component((#c_expr,))
+                            })
                         }
                     }
                 }
@@ -586,7 +861,8 @@ impl quote::ToTokens for ManaAttr {
             fn_name,
             assign,
         } = self;
-        let tok = quote! {
+        // a method that doesn't exist is reported on its name
+        let tok = quote_spanned! { fn_name.span() =>
             .#fn_name(
                 #assign
             )
@@ -598,13 +874,19 @@ impl quote::ToTokens for ManaAttr {
 impl quote::ToTokens for ManaAttrAssign {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let ManaAttrAssign { _eq, value } = self;
-        let tok = quote! {
-            #[allow(unused_braces)]
-            {
-                #value
-            }
-        };
-        tokens.extend(tok);
+        match value {
+            ManaAttrValue::ExprBlock(block) => tokens.extend(unbraced(block)),
+            value => value.to_tokens(tokens),
+        }
+    }
+}
+
+/// the expression of a single-expression block, since `{ value }` would trip `unused_braces`.
+fn unbraced(block: &syn::ExprBlock) -> TokenStream {
+    if let [syn::Stmt::Expr(expr, None)] = block.block.stmts.as_slice() {
+        quote! { #expr }
+    } else {
+        quote! { #block }
     }
 }
 
@@ -613,13 +895,22 @@ impl quote::ToTokens for Element {
         let Element {
             open,
             children,
+            slots,
             close: _close,
         } = self;
-        let init = quote! { #open };
-        let children = quote! { #children };
-        tokens.extend(quote! {
-            #init #children
-        });
+        let data = &open.data;
+        let widget = data.widget();
+        let view = if !slots.is_empty() {
+            // only subviews have slots, so the rest of the children can be set directly
+            let children = (!children.is_empty()).then(|| quote! { .children(#children) });
+            quote! { #widget #(#slots)* #children .into_view() }
+        } else if !children.is_empty() {
+            let mana_crate = mana_tui_elemental();
+            quote! { #mana_crate::ui::WithChildren::with_children(#widget, #children) }
+        } else {
+            quote! { #widget.into_view() }
+        };
+        tokens.extend(data.element(&view));
     }
 }
 
@@ -627,21 +918,60 @@ impl quote::ToTokens for TextElement {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let TextElement {
             open,
+            tag,
             text,
+            markup,
             close: _,
         } = self;
-        let constructor = match open.data.ident.to_string().as_str() {
-            "Text" | "Line" | "Span" => quote! { raw },
-            "Paragraph" => quote! { new },
-            _ => todo!(),
-        };
+        let constructor = tag.constructor();
         let ManaTagData {
             ref ident,
             ref attrs,
+            ref handlers,
             ref components,
+            ..
         } = open.data;
+        // markdown has its own syntax, brackets are links
+        if *tag == TextTag::Markdown {
+            return tokens.extend(quote! {
+                __ui_internal(#ident::#constructor(&format!(#text)) #attrs .into_view())
+                    #(#handlers)* #components
+            });
+        }
+        // brackets are plain text unless markup was asked for
+        let markup = match markup.then(|| Markup::parse(text)).transpose() {
+            Ok(markup) => markup.flatten(),
+            Err(err) => return tokens.extend(err.to_compile_error()),
+        };
+        // paragraphs keep their text private, so it is expanded before they are built
+        if *tag == TextTag::Paragraph && text.value().contains('\t') {
+            let mana_crate = mana_tui_elemental();
+            let value = match markup {
+                Some(markup) => markup.text(text),
+                None => quote! { format!(#text) },
+            };
+            return tokens.extend(quote! {
+                #mana_crate::unicode::expand_tabs(
+                    #value,
+                    move |__text| #ident::#constructor(__text) #attrs,
+                ) #(#handlers)* #components
+            });
+        }
+        let value = match (markup, tag) {
+            (None, _) => quote! { #ident::#constructor(format!(#text)) },
+            (Some(markup), TextTag::Text) => markup.text(text),
+            (Some(markup), TextTag::Line) => markup.line(text),
+            (Some(markup), TextTag::Span) => match markup.span(text) {
+                Ok(span) => span,
+                Err(err) => return tokens.extend(err.to_compile_error()),
+            },
+            (Some(markup), _) => {
+                let text = markup.text(text);
+                quote! { #ident::#constructor(#text) }
+            }
+        };
         let out = quote! {
-            __ui_internal(#ident::#constructor(format!(#text)) #attrs .into_view())#components
+            __ui_internal(#value #attrs .into_view()) #(#handlers)* #components
         };
         tokens.extend(out);
     }
@@ -649,18 +979,31 @@ impl quote::ToTokens for TextElement {
 
 impl quote::ToTokens for ChildrenList {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        if self.0.is_empty() {
-            return;
-        }
-        let tok = self
+        let mana_crate = mana_tui_elemental();
+        // a vec fits both widgets and the `impl AsChildren` parameters of subviews
+        if !self
             .0
             .iter()
-            .map(|el| quote! { #el })
-            .reduce(|acc, el| quote! {#acc, #el});
-        let fncall = quote! {
-            .children((#tok,))
-        };
-        tokens.extend(fncall);
+            .any(|el| matches!(el, ManaElement::ControlFlow(_)))
+        {
+            let elements = &self.0;
+            tokens.extend(quote! {
+                ::std::vec::Vec::<#mana_crate::ui::View>::from([#(#elements),*])
+            });
+            return;
+        }
+        // control flow adds any number of children
+        let children = format_ident!("__children");
+        let push = control_flow::push_children(&self.0, &children);
+        tokens.extend(quote! {
+            {
+                #[allow(unused_mut)]
+                let mut #children: ::std::vec::Vec<#mana_crate::ui::View> =
+                    ::std::vec::Vec::new();
+                #push
+                #children
+            }
+        });
     }
 }
 
@@ -681,12 +1024,7 @@ impl quote::ToTokens for BraceBlock {
 impl quote::ToTokens for ChildrenBlock {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let ChildrenBlock(block) = self;
-        let fncall = quote! {
-            .children(
-                #block
-            )
-        };
-        tokens.extend(fncall);
+        block.to_tokens(tokens);
     }
 }
 
@@ -0,0 +1,328 @@
+//! inline style markup in text literals.
+//!
+//! text tags with the `.markup` attribute can style parts of their literal with tags:
+//! `<Text .markup>"[b]bold[/b] [fg=red]{count}[/fg]"</Text>`. tags are compiled into styled
+//! spans and lines at macro time. `[[` is a literal `[`, and brackets that don't hold a known tag
+//! are kept as text. literals without `.markup` are never parsed, so their brackets are plain
+//! text.
+//!
+//! | tag                       | style                    |
+//! |---------------------------|--------------------------|
+//! | `[b]`, `[bold]`           | bold                     |
+//! | `[i]`, `[italic]`         | italic                   |
+//! | `[u]`, `[underline]`      | underlined               |
+//! | `[s]`, `[strike]`         | crossed out              |
+//! | `[dim]`                   | dim                      |
+//! | `[rev]`, `[reversed]`     | reversed                 |
+//! | `[blink]`                 | slow blink               |
+//! | `[fg=color]`              | foreground color         |
+//! | `[bg=color]`              | background color         |
+//!
+//! tags are closed with `[/name]` (e.g. `[/b]`, `[/fg]`) or `[/]`, which closes the last open tag.
+//! tags left open style the rest of the literal. colors are ratatui color names (`red`,
+//! `light_blue`, `dark_gray`...), `#rrggbb` or an index from 0 to 255.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tag {
+    Modifier(&'static str),
+    Fg(Color),
+    Bg(Color),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Color {
+    Named(&'static str),
+    Rgb(u8, u8, u8),
+    Indexed(u8),
+}
+
+/// what a closing tag closes. aliases resolve to the same kind, so `[bold]` closes with `[/b]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TagKind {
+    Modifier(&'static str),
+    Fg,
+    Bg,
+}
+
+impl Tag {
+    fn kind(&self) -> TagKind {
+        match self {
+            Self::Modifier(modifier) => TagKind::Modifier(modifier),
+            Self::Fg(_) => TagKind::Fg,
+            Self::Bg(_) => TagKind::Bg,
+        }
+    }
+}
+
+/// a run of text with a single style. `text` is a `format!` string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Segment {
+    text: String,
+    tags: Vec<Tag>,
+}
+
+/// a parsed text literal, split into lines of styled segments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Markup {
+    lines: Vec<Vec<Segment>>,
+}
+
+enum Token {
+    Open(Tag),
+    /// a closing tag as written, and what it closes. `[/]` closes the last open tag.
+    Close(String, Option<TagKind>),
+}
+
+const MODIFIERS: &[(&[&str], &str)] = &[
+    (&["b", "bold"], "BOLD"),
+    (&["i", "italic"], "ITALIC"),
+    (&["u", "underline"], "UNDERLINED"),
+    (&["s", "strike"], "CROSSED_OUT"),
+    (&["dim"], "DIM"),
+    (&["rev", "reversed"], "REVERSED"),
+    (&["blink"], "SLOW_BLINK"),
+];
+
+const COLORS: &[(&str, &str)] = &[
+    ("reset", "Reset"),
+    ("black", "Black"),
+    ("red", "Red"),
+    ("green", "Green"),
+    ("yellow", "Yellow"),
+    ("blue", "Blue"),
+    ("magenta", "Magenta"),
+    ("cyan", "Cyan"),
+    ("gray", "Gray"),
+    ("dark_gray", "DarkGray"),
+    ("light_red", "LightRed"),
+    ("light_green", "LightGreen"),
+    ("light_yellow", "LightYellow"),
+    ("light_blue", "LightBlue"),
+    ("light_magenta", "LightMagenta"),
+    ("light_cyan", "LightCyan"),
+    ("white", "White"),
+];
+
+impl Markup {
+    /// parses the markup in `literal`. returns `None` if it has no tags or escapes, so plain
+    /// literals keep rendering as raw text.
+    pub fn parse(literal: &syn::LitStr) -> syn::Result<Option<Self>> {
+        let source = literal.value();
+        let mut lines = vec![Vec::new()];
+        let mut open: Vec<Tag> = Vec::new();
+        let mut text = String::new();
+        let mut has_markup = false;
+
+        let flush = |text: &mut String, lines: &mut Vec<Vec<Segment>>, open: &[Tag]| {
+            if text.is_empty() {
+                return;
+            }
+            let segment = Segment {
+                text: std::mem::take(text),
+                tags: open.to_vec(),
+            };
+            lines
+                .last_mut()
+                .expect("there is always a line")
+                .push(segment);
+        };
+
+        let mut rest = source.as_str();
+        while let Some(ch) = rest.chars().next() {
+            match ch {
+                '\n' => {
+                    flush(&mut text, &mut lines, &open);
+                    lines.push(Vec::new());
+                    rest = &rest[1..];
+                }
+                // format arguments are copied as is, their specs may contain brackets
+                '{' if rest.starts_with("{{") => {
+                    text.push_str("{{");
+                    rest = &rest[2..];
+                }
+                '{' => {
+                    let end = rest.find('}').map_or(rest.len(), |idx| idx + 1);
+                    text.push_str(&rest[..end]);
+                    rest = &rest[end..];
+                }
+                '[' if rest.starts_with("[[") => {
+                    has_markup = true;
+                    text.push('[');
+                    rest = &rest[2..];
+                }
+                '[' => {
+                    let token = rest
+                        .find(']')
+                        .map(|end| (end, parse_tag(&rest[1..end], literal)))
+                        .and_then(|(end, token)| Some((end, token.transpose()?)));
+                    let Some((end, token)) = token else {
+                        text.push('[');
+                        rest = &rest[1..];
+                        continue;
+                    };
+                    has_markup = true;
+                    flush(&mut text, &mut lines, &open);
+                    match token? {
+                        Token::Open(tag) => open.push(tag),
+                        Token::Close(_, None) => {
+                            if open.pop().is_none() {
+                                return Err(syn::Error::new(
+                                    literal.span(),
+                                    "`[/]` does not close any tag",
+                                ));
+                            }
+                        }
+                        Token::Close(name, Some(kind)) => {
+                            let Some(idx) = open.iter().rposition(|open| open.kind() == kind)
+                            else {
+                                return Err(syn::Error::new(
+                                    literal.span(),
+                                    format!("`[/{name}]` does not close any tag"),
+                                ));
+                            };
+                            open.remove(idx);
+                        }
+                    }
+                    rest = &rest[end + 1..];
+                }
+                _ => {
+                    text.push(ch);
+                    rest = &rest[ch.len_utf8()..];
+                }
+            }
+        }
+        flush(&mut text, &mut lines, &open);
+
+        Ok(has_markup.then_some(Self { lines }))
+    }
+
+    /// the markup as a `ratatui::text::Text`.
+    pub fn text(&self, literal: &syn::LitStr) -> TokenStream {
+        let lines = self.lines.iter().map(|line| line_tokens(line, literal));
+        quote! { ::ratatui::text::Text::from(vec![#(#lines),*]) }
+    }
+
+    /// the markup as a single `ratatui::text::Line`. line breaks are dropped.
+    pub fn line(&self, literal: &syn::LitStr) -> TokenStream {
+        let segments: Vec<_> = self.lines.iter().flatten().cloned().collect();
+        line_tokens(&segments, literal)
+    }
+
+    /// the markup as a single `ratatui::text::Span`. fails if the literal has more than one
+    /// style, since a span can only hold one.
+    pub fn span(&self, literal: &syn::LitStr) -> syn::Result<TokenStream> {
+        let segments: Vec<_> = self.lines.iter().flatten().collect();
+        match segments.as_slice() {
+            [] => Ok(quote! { ::ratatui::text::Span::default() }),
+            [segment] => Ok(span_tokens(segment, literal)),
+            _ => Err(syn::Error::new(
+                literal.span(),
+                "a `Span` can only have one style, use `Line` or `Text` to mix styles",
+            )),
+        }
+    }
+}
+
+fn parse_tag(source: &str, literal: &syn::LitStr) -> syn::Result<Option<Token>> {
+    if let Some(name) = source.strip_prefix('/') {
+        let kind = match name {
+            "" => None,
+            "fg" => Some(TagKind::Fg),
+            "bg" => Some(TagKind::Bg),
+            name => match modifier(name) {
+                Some(modifier) => Some(TagKind::Modifier(modifier)),
+                None => return Ok(None),
+            },
+        };
+        return Ok(Some(Token::Close(name.to_string(), kind)));
+    }
+    let (name, value) = match source.split_once('=') {
+        Some((name, value)) => (name, Some(value)),
+        None => (source, None),
+    };
+    let tag = match (name, value) {
+        ("fg", Some(color)) => Tag::Fg(parse_color(color, literal)?),
+        ("bg", Some(color)) => Tag::Bg(parse_color(color, literal)?),
+        (name, None) => {
+            let Some(modifier) = modifier(name) else {
+                return Ok(None);
+            };
+            Tag::Modifier(modifier)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(Token::Open(tag)))
+}
+
+/// the `Modifier` constant named by `name` or one of its aliases.
+fn modifier(name: &str) -> Option<&'static str> {
+    MODIFIERS
+        .iter()
+        .find(|(names, _)| names.contains(&name))
+        .map(|&(_, modifier)| modifier)
+}
+
+fn parse_color(source: &str, literal: &syn::LitStr) -> syn::Result<Color> {
+    let name = source.to_lowercase().replace(['-', ' '], "_");
+    if let Some((_, color)) = COLORS.iter().find(|(color, _)| *color == name) {
+        return Ok(Color::Named(color));
+    }
+    if let Some(hex) = name.strip_prefix('#')
+        && hex.len() == 6
+        && let Ok(rgb) = u32::from_str_radix(hex, 16)
+    {
+        let [_, r, g, b] = rgb.to_be_bytes();
+        return Ok(Color::Rgb(r, g, b));
+    }
+    if let Ok(idx) = name.parse() {
+        return Ok(Color::Indexed(idx));
+    }
+    Err(syn::Error::new(
+        literal.span(),
+        format!("unknown color `{source}`, expected a color name, `#rrggbb` or 0-255"),
+    ))
+}
+
+fn line_tokens(segments: &[Segment], literal: &syn::LitStr) -> TokenStream {
+    let spans = segments.iter().map(|segment| span_tokens(segment, literal));
+    quote! { ::ratatui::text::Line::from(vec![#(#spans),*]) }
+}
+
+fn span_tokens(segment: &Segment, literal: &syn::LitStr) -> TokenStream {
+    // keep the span of the literal so format arguments resolve where they were written
+    let text = syn::LitStr::new(&segment.text, literal.span());
+    if segment.tags.is_empty() {
+        return quote! { ::ratatui::text::Span::raw(format!(#text)) };
+    }
+    let style = segment.tags.iter().map(|tag| match tag {
+        Tag::Modifier(modifier) => {
+            let modifier = syn::Ident::new(modifier, literal.span());
+            quote! { .add_modifier(::ratatui::style::Modifier::#modifier) }
+        }
+        Tag::Fg(color) => {
+            let color = color_tokens(color, literal);
+            quote! { .fg(#color) }
+        }
+        Tag::Bg(color) => {
+            let color = color_tokens(color, literal);
+            quote! { .bg(#color) }
+        }
+    });
+    quote! {
+        ::ratatui::text::Span::styled(format!(#text), ::ratatui::style::Style::new() #(#style)*)
+    }
+}
+
+fn color_tokens(color: &Color, literal: &syn::LitStr) -> TokenStream {
+    match color {
+        Color::Named(name) => {
+            let name = syn::Ident::new(name, literal.span());
+            quote! { ::ratatui::style::Color::#name }
+        }
+        Color::Rgb(r, g, b) => quote! { ::ratatui::style::Color::Rgb(#r, #g, #b) },
+        Color::Indexed(idx) => quote! { ::ratatui::style::Color::Indexed(#idx) },
+    }
+}
@@ -0,0 +1,214 @@
+//! `#[prop(..)]` parameters of subviews and the props struct that `ui!` spreads.
+//!
+//! ```text
+//! #[subview]
+//! fn button(label: &str, #[prop(default)] size: u16, #[prop(into, default = "ok")] hint: String)
+//! ```
+//!
+//! `#[prop(..)]` is bon's `#[builder(..)]` for parameters. the parameters with a default also go
+//! into a `ButtonProps` struct, which `<Button ..{props} />` sets all at once.
+
+use std::collections::HashSet;
+
+use convert_case::Casing;
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{format_ident, quote};
+
+use crate::utils::mana_tui_elemental;
+
+/// a parameter with a default.
+#[derive(Debug, Clone)]
+pub struct Prop {
+    pub ident: syn::Ident,
+    default: syn::Expr,
+}
+
+/// replaces the `#[prop(..)]` attributes of `func` with bon's `#[builder(..)]`, returning the
+/// parameters that have a default.
+pub fn take_props(func: &mut syn::ItemFn) -> syn::Result<Vec<Prop>> {
+    let mut props = Vec::new();
+    for arg in &mut func.sig.inputs {
+        let syn::FnArg::Typed(pat_type) = arg else {
+            continue;
+        };
+        let mut default = None;
+        let mut into = false;
+        let mut is_prop = false;
+        let mut error = None;
+        pat_type.attrs.retain(|attr| {
+            if !attr.path().is_ident("prop") {
+                return true;
+            }
+            is_prop = true;
+            let res = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default") {
+                    default = Some(if meta.input.peek(syn::Token![=]) {
+                        meta.value()?.parse()?
+                    } else {
+                        syn::parse_quote! { ::core::default::Default::default() }
+                    });
+                    Ok(())
+                } else if meta.path.is_ident("into") {
+                    into = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown prop option, expected `default` or `into`"))
+                }
+            });
+            if let Err(err) = res {
+                error.get_or_insert(err);
+            }
+            false
+        });
+        if let Some(err) = error {
+            return Err(err);
+        }
+        if !is_prop {
+            continue;
+        }
+        let syn::Pat::Ident(pat) = pat_type.pat.as_ref() else {
+            return Err(syn::Error::new_spanned(
+                &pat_type.pat,
+                "props need a plain name, like `size: u16`",
+            ));
+        };
+        let ident = pat.ident.clone();
+        if into {
+            pat_type.attrs.push(syn::parse_quote! { #[builder(into)] });
+        }
+        let Some(default) = default else {
+            continue;
+        };
+        if let syn::Type::ImplTrait(ty) = pat_type.ty.as_ref() {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "a prop with a default needs a concrete type",
+            ));
+        }
+        pat_type
+            .attrs
+            .push(syn::parse_quote! { #[builder(default = #default)] });
+        // bon converts the default of an `into` prop too
+        let default = if into {
+            syn::parse_quote! { ::core::convert::Into::into(#default) }
+        } else {
+            default
+        };
+        props.push(Prop { ident, default });
+    }
+    Ok(props)
+}
+
+/// the props struct of the subview and its `Default` and `Spread` implementations.
+///
+/// `props` holds the type of each prop as the builder names it, and `generics` are the generics of
+/// the builder.
+pub fn props_struct(
+    func: &syn::ItemFn,
+    name: &syn::Ident,
+    builder_module: &syn::Ident,
+    generics: &syn::Generics,
+    props: &[(Prop, syn::Type)],
+) -> TokenStream {
+    let mana_crate = mana_tui_elemental();
+    let vis = &func.vis;
+    let props_name = format_ident!("{name}Props");
+
+    // the struct only takes the generics its fields use
+    let mut used = HashSet::new();
+    for (_, ty) in props {
+        collect_idents(quote! { #ty }, &mut used);
+    }
+    let mut props_generics = syn::Generics::default();
+    for param in &generics.params {
+        let used = match param {
+            syn::GenericParam::Lifetime(param) => used.contains(&param.lifetime.to_string()),
+            syn::GenericParam::Type(param) => used.contains(&param.ident.to_string()),
+            syn::GenericParam::Const(param) => used.contains(&param.ident.to_string()),
+        };
+        if used {
+            props_generics.params.push(param.clone());
+        }
+    }
+    let (props_impl, props_ty, _) = props_generics.split_for_impl();
+
+    let fields = props
+        .iter()
+        .map(|(prop, _)| &prop.ident)
+        .collect::<Vec<_>>();
+    let types = props.iter().map(|(_, ty)| ty);
+    let defaults = props.iter().map(|(prop, _)| &prop.default);
+
+    // the builder state after every prop is set
+    let mut state = quote! { S };
+    for field in &fields {
+        let member = field.to_string().to_case(convert_case::Case::Pascal);
+        let set = format_ident!("Set{member}");
+        state = quote! { #builder_module::#set<#state> };
+    }
+    let lifetimes = generics.lifetimes().map(|param| &param.lifetime);
+    let params = generics.params.iter().filter_map(|param| match param {
+        syn::GenericParam::Lifetime(_) => None,
+        syn::GenericParam::Type(param) if param.ident == "S" => Some(state.clone()),
+        syn::GenericParam::Type(syn::TypeParam { ident, .. })
+        | syn::GenericParam::Const(syn::ConstParam { ident, .. }) => Some(quote! { #ident }),
+    });
+    let output = quote! { #name<#(#lifetimes,)* #(#params),*> };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut where_clause = where_clause.cloned();
+    if let Some(wh) = &mut where_clause {
+        for field in &fields {
+            let member = format_ident!("{}", field.to_string().to_case(convert_case::Case::Pascal));
+            wh.predicates.push(syn::parse_quote! {
+                S::#member: #builder_module::IsUnset
+            });
+        }
+    }
+    let doc = format!(
+        "the props of [`{}`] that have a default, set all at once with `<{name} ..{{props}} />`.",
+        func.sig.ident
+    );
+
+    quote! {
+        #[doc = #doc]
+        #[allow(missing_docs)]
+        #vis struct #props_name #props_impl {
+            #(pub #fields: #types,)*
+        }
+
+        impl #props_impl Default for #props_name #props_ty {
+            fn default() -> Self {
+                Self {
+                    #(#fields: #defaults,)*
+                }
+            }
+        }
+
+        impl #impl_generics #mana_crate::ui::Spread<#props_name #props_ty> for #name #ty_generics
+        #where_clause
+        {
+            type Output = #output;
+
+            fn spread(self, props: #props_name #props_ty) -> Self::Output {
+                self #(.#fields(props.#fields))*
+            }
+        }
+    }
+}
+
+/// collects the identifiers and lifetimes in `tokens`.
+fn collect_idents(tokens: TokenStream, out: &mut HashSet<String>) {
+    // a lifetime is a `'` followed by an identifier
+    let mut lifetime = false;
+    for token in tokens {
+        let quote = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == '\'');
+        match token {
+            TokenTree::Group(group) => collect_idents(group.stream(), out),
+            TokenTree::Ident(ident) if lifetime => _ = out.insert(format!("'{ident}")),
+            TokenTree::Ident(ident) => _ = out.insert(ident.to_string()),
+            TokenTree::Punct(_) | TokenTree::Literal(_) => {}
+        }
+        lifetime = quote;
+    }
+}
@@ -0,0 +1,136 @@
+//! named slots of subviews in `ui!` markup.
+//!
+//! ```text
+//! <Card .title="hello">
+//!     <Card:header>"header"</Card:header>
+//!     <Card:footer />
+//!     "the rest goes to `children`"
+//! </Card>
+//! ```
+//!
+//! a slot passes its children to the subview parameter of the same name, the same way nested
+//! markup is passed to `children`. slots must be direct children of the subview they belong to.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote_spanned;
+use syn::{
+    Token,
+    parse::{Parse, ParseStream},
+};
+
+use crate::manasx::Children;
+
+#[derive(Debug, Clone)]
+pub struct Slot {
+    span: Span,
+    parent: syn::Ident,
+    name: syn::Ident,
+    children: Children,
+}
+
+impl Slot {
+    /// whether `input` starts with a slot, i.e. `<Name:`.
+    pub fn peek(input: ParseStream) -> bool {
+        input.peek(Token![<])
+            && input.peek2(syn::Ident)
+            && input.peek3(Token![:])
+            && !input.peek3(Token![::])
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// checks that the slot is a direct child of `parent`.
+    pub fn check_parent(&self, parent: &str) -> syn::Result<()> {
+        if self.parent == parent {
+            return Ok(());
+        }
+        Err(syn::Error::new(
+            self.parent.span(),
+            format!(
+                "<{}:{}> is a slot of <{}>, not <{parent}>",
+                self.parent, self.name, self.parent
+            ),
+        ))
+    }
+}
+
+impl Parse for Slot {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let lt = input.parse::<Token![<]>()?;
+        let parent = input.parse::<syn::Ident>()?;
+        input.parse::<Token![:]>()?;
+        let name = input
+            .parse::<syn::Ident>()
+            .map_err(|err| syn::Error::new(err.span(), "expected a slot name after `:`"))?;
+        let self_closing = input.parse::<Option<Token![/]>>()?.is_some();
+        let gt = input.parse::<Token![>]>()?;
+        let span = lt.span.join(gt.span).unwrap_or(name.span());
+        if self_closing {
+            return Ok(Self {
+                span,
+                parent,
+                name,
+                children: Children::empty(),
+            });
+        }
+
+        let children = input.parse()?;
+        if input.is_empty() {
+            return Err(syn::Error::new(
+                span,
+                format!("unclosed slot <{parent}:{name}>"),
+            ));
+        }
+        input.parse::<Token![<]>()?;
+        input.parse::<Token![/]>()?;
+        let close_parent = input.parse::<syn::Ident>()?;
+        let close = if input.peek(Token![:]) {
+            input.parse::<Token![:]>()?;
+            let close_name = input.parse::<syn::Ident>()?;
+            format!("{close_parent}:{close_name}")
+        } else {
+            close_parent.to_string()
+        };
+        if close != format!("{parent}:{name}") {
+            let mut err = syn::Error::new(
+                close_parent.span(),
+                format!("closing tag </{close}> does not match opening <{parent}:{name}>"),
+            );
+            err.combine(syn::Error::new(
+                span,
+                format!("<{parent}:{name}> is opened here"),
+            ));
+            return Err(err);
+        }
+        input.parse::<Token![>]>()?;
+        Ok(Self {
+            span,
+            parent,
+            name,
+            children,
+        })
+    }
+}
+
+/// checks that no slot is given twice, since each one is a single parameter.
+pub fn check_unique(slots: &[Slot]) -> syn::Result<()> {
+    for (idx, slot) in slots.iter().enumerate() {
+        if slots[..idx].iter().any(|other| other.name == slot.name) {
+            return Err(syn::Error::new(
+                slot.name.span(),
+                format!("<{}:{}> is given twice", slot.parent, slot.name),
+            ));
+        }
+    }
+    Ok(())
+}
+
+impl quote::ToTokens for Slot {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Slot { name, children, .. } = self;
+        // a subview without the parameter is reported on the slot name
+        tokens.extend(quote_spanned! { name.span() => .#name(#children) });
+    }
+}
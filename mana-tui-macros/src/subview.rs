@@ -2,23 +2,25 @@ use convert_case::Casing;
 use quote::{format_ident, quote_spanned};
 use syn::{PatType, spanned::Spanned};
 
+use crate::props::{self, Prop};
 use crate::utils::mana_tui_elemental;
 
 pub struct SubviewFn {
     func: syn::ItemFn,
+    props: Vec<Prop>,
 }
 
 impl syn::parse::Parse for SubviewFn {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        Ok(Self {
-            func: input.parse()?,
-        })
+        let mut func = input.parse()?;
+        let props = props::take_props(&mut func)?;
+        Ok(Self { func, props })
     }
 }
 
 impl quote::ToTokens for SubviewFn {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        let SubviewFn { func } = self;
+        let SubviewFn { func, .. } = self;
         let generics = &func.sig.generics;
         let impl_trait_params = func
             .sig
@@ -78,6 +80,22 @@ impl quote::ToTokens for SubviewFn {
                 }));
             wh
         });
+        let children = format_ident!("children");
+        let with_children = match member_ty(func, &impl_trait_params, &children) {
+            Some(children_ty) => with_children_impl(
+                &name,
+                &builder_module,
+                &builder_generics.full_generics,
+                &children_ty,
+            ),
+            None => without_children_impl(&name, &builder_generics.full_generics),
+        };
+        let props_struct = self.props_struct(
+            &name,
+            &builder_module,
+            &builder_generics.full_generics,
+            &impl_trait_params,
+        );
         let span = func_name.span();
 
         let tok = quote_spanned! {
@@ -103,11 +121,145 @@ impl quote::ToTokens for SubviewFn {
                     value.into_view()
                 }
             }
+
+            #with_children
+
+            #props_struct
         };
         tokens.extend(tok);
     }
 }
 
+impl SubviewFn {
+    /// the props struct, if any parameter has a default.
+    fn props_struct(
+        &self,
+        name: &syn::Ident,
+        builder_module: &syn::Ident,
+        generics: &syn::Generics,
+        impl_trait_params: &[(&PatType, syn::TypeImplTrait)],
+    ) -> Option<proc_macro2::TokenStream> {
+        if self.props.is_empty() {
+            return None;
+        }
+        let props = self
+            .props
+            .iter()
+            .map(|prop| {
+                let ty = member_ty(&self.func, impl_trait_params, &prop.ident)
+                    .expect("props are parameters");
+                (prop.clone(), ty)
+            })
+            .collect::<Vec<_>>();
+        Some(props::props_struct(
+            &self.func,
+            name,
+            builder_module,
+            generics,
+            &props,
+        ))
+    }
+}
+
+/// implements `WithChildren` for the builder, so nested markup is passed as `children`.
+fn with_children_impl(
+    name: &syn::Ident,
+    builder_module: &syn::Ident,
+    generics: &syn::Generics,
+    children_ty: &syn::Type,
+) -> proc_macro2::TokenStream {
+    let mana_crate = mana_tui_elemental();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut where_clause = where_clause.cloned();
+    if let Some(wh) = &mut where_clause {
+        wh.predicates.push(syn::parse_quote! {
+            S::Children: #builder_module::IsUnset
+        });
+        wh.predicates.push(syn::parse_quote! {
+            #builder_module::SetChildren<S>: #builder_module::IsComplete
+        });
+    }
+    quote::quote! {
+        impl #impl_generics
+            #mana_crate::ui::WithChildren<#children_ty, #mana_crate::ui::SubviewMarker>
+            for #name #ty_generics
+        #where_clause
+        {
+            fn with_children(self, children: #children_ty) -> #mana_crate::ui::View {
+                self.children(children).into_view()
+            }
+        }
+    }
+}
+
+/// implements `WithChildren` for a builder without a `children` parameter, bounded by the
+/// unimplemented `TakesChildren`. nested markup is then an error about the subview, and never
+/// falls through to the implementation of widgets.
+fn without_children_impl(name: &syn::Ident, generics: &syn::Generics) -> proc_macro2::TokenStream {
+    let mana_crate = mana_tui_elemental();
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+    let mut impl_generics = generics.clone();
+    impl_generics.params.push(syn::parse_quote! { __C });
+    let (impl_generics, _, _) = impl_generics.split_for_impl();
+    let mut where_clause = where_clause.cloned();
+    if let Some(wh) = &mut where_clause {
+        wh.predicates.push(syn::parse_quote! {
+            Self: #mana_crate::ui::TakesChildren
+        });
+    }
+    quote::quote! {
+        impl #impl_generics #mana_crate::ui::WithChildren<__C, #mana_crate::ui::SubviewMarker>
+            for #name #ty_generics
+        #where_clause
+        {
+            fn with_children(self, _: __C) -> #mana_crate::ui::View {
+                unreachable!("`TakesChildren` is not implemented")
+            }
+        }
+    }
+}
+
+/// the type of the parameter named `member` as the builder names it, if there is one.
+///
+/// `impl Trait` parameters become the `T{idx}` generics and references without a lifetime get the
+/// `'f{n}` lifetimes of [`BuilderGenerics`].
+fn member_ty(
+    func: &syn::ItemFn,
+    impl_trait_params: &[(&PatType, syn::TypeImplTrait)],
+    member: &syn::Ident,
+) -> Option<syn::Type> {
+    let mut implicit_lifetimes = 0;
+    for arg in &func.sig.inputs {
+        let syn::FnArg::Typed(pat_type) = arg else {
+            continue;
+        };
+        let elided = match pat_type.ty.as_ref() {
+            syn::Type::Reference(reference) if reference.lifetime.is_none() => {
+                implicit_lifetimes += 1;
+                Some(reference)
+            }
+            _ => None,
+        };
+        if !matches!(pat_type.pat.as_ref(), syn::Pat::Ident(pat) if pat.ident == *member) {
+            continue;
+        }
+        if let Some(idx) = impl_trait_params
+            .iter()
+            .position(|(param, _)| std::ptr::eq(*param, pat_type))
+        {
+            let ident = format_ident!("T{idx}");
+            return Some(syn::parse_quote! { #ident });
+        }
+        let mut ty = pat_type.ty.as_ref().clone();
+        if let (Some(reference), syn::Type::Reference(ty)) = (elided, &mut ty) {
+            let lifetime = format!("'f{implicit_lifetimes}");
+            ty.lifetime = Some(syn::Lifetime::new(&lifetime, reference.span()));
+        }
+        return Some(ty);
+    }
+    None
+}
+
 #[derive(Debug, Clone)]
 struct BuilderGenerics {
     implicit_lifetimes: syn::Generics,
@@ -5,3 +5,17 @@ pub fn mana_tui_elemental() -> proc_macro2::TokenStream {
         ::mana_tui::mana_tui_elemental
     }
 }
+
+pub fn mana_tui_beheaded() -> proc_macro2::TokenStream {
+    quote! {
+        ::mana_tui::mana_tui_beheaded
+    }
+}
+
+/// whether the crate being compiled uses the potion runtime, which handles events with its own
+/// components. cargo sets `CARGO_MANIFEST_DIR` to the crate the macro expands in.
+pub fn uses_potion() -> bool {
+    std::env::var_os("CARGO_MANIFEST_DIR")
+        .and_then(|dir| std::fs::read_to_string(std::path::Path::new(&dir).join("Cargo.toml")).ok())
+        .is_some_and(|manifest| manifest.contains("mana-tui-potion"))
+}
@@ -0,0 +1,9 @@
+use mana_tui_macros::ui;
+
+fn main() {
+    let _ = ui! {
+        <Block>
+            <Card:header>"title"</Card:header>
+        </Block>
+    };
+}
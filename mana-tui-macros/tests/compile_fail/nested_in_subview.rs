@@ -0,0 +1,17 @@
+use mana_tui::prelude::*;
+use mana_tui_macros::{subview, ui};
+
+#[subview]
+fn badge(label: &'static str) -> View {
+    ui! {
+        <Block .title_top={label} />
+    }
+}
+
+fn main() {
+    let _ = ui! {
+        <Badge .label="new">
+            "replaced"
+        </Badge>
+    };
+}
@@ -0,0 +1,10 @@
+use std::rc::Rc;
+
+use mana_tui::prelude::*;
+use mana_tui_macros::ui;
+
+fn main() {
+    let _ = ui! {
+        <Block {Rc::new(0)} />
+    };
+}
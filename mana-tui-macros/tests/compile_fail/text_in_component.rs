@@ -0,0 +1,7 @@
+use mana_tui_macros::ui;
+
+fn main() {
+    let _ = ui! {
+        <Block "hello" />
+    };
+}
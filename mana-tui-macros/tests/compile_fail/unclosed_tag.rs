@@ -0,0 +1,8 @@
+use mana_tui_macros::ui;
+
+fn main() {
+    let _ = ui! {
+        <Block .title_top="sidebar">
+            <Text>"unclosed"</Text>
+    };
+}
@@ -0,0 +1,11 @@
+use mana_tui::prelude::*;
+use mana_tui_macros::ui;
+
+#[derive(Default)]
+struct Card;
+
+fn main() {
+    let _ = ui! {
+        <Card .frobnicate={1} />
+    };
+}
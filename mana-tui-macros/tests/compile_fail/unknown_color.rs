@@ -0,0 +1,10 @@
+use mana_tui::prelude::*;
+use mana_tui_macros::ui;
+
+fn main() {
+    let _ = ui! {
+        <Block>
+            <Text .markup>"[fg=blurple]hi"</Text>
+        </Block>
+    };
+}
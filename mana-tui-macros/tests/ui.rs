@@ -18,6 +18,40 @@ fn container(justify: MainJustify, children: impl AsChildren) -> View {
     }
 }
 
+#[subview]
+fn row(children: impl AsChildren) -> View {
+    ui! {
+        <Block Direction::Horizontal Gap(1)>
+            {children}
+        </Block>
+    }
+}
+
+#[subview]
+fn card(header: impl AsChildren, footer: impl AsChildren, children: impl AsChildren) -> View {
+    ui! {
+        <Block Width::grow()>
+            <Row>{header}</Row>
+            <Row>{children}</Row>
+            <Row>{footer}</Row>
+        </Block>
+    }
+}
+
+#[subview]
+fn badge(
+    label: &str,
+    #[prop(default = '*')] icon: char,
+    #[prop(into, default = "")] suffix: String,
+) -> View {
+    ui! {
+        <Block Direction::Horizontal Gap(1)>
+            "{icon}"
+            "{label}{suffix}"
+        </Block>
+    }
+}
+
 #[test]
 fn test() {
     let _ = ui! {
@@ -93,3 +127,157 @@ fn test() {
         </Block>
     };
 }
+
+#[test]
+fn test_control_flow() {
+    use mana_tui::testing;
+
+    #[derive(Clone, Copy)]
+    enum Status {
+        Done,
+        Todo(u8),
+    }
+
+    let items = [
+        ("milk", Status::Done),
+        ("eggs", Status::Todo(2)),
+        ("tea", Status::Todo(0)),
+    ];
+    let show_header = false;
+    let view = ui! {
+        <Block Width::fixed(12) Height::fixed(4)>
+            {#if show_header {
+                "header"
+            } else if items.is_empty() {
+                "empty"
+            }}
+            {#for (name, status) in items {
+                <Block Direction::Horizontal Gap(1)>
+                    {#match status {
+                        Status::Done => "[x]",
+                        Status::Todo(count) if count > 0 => {
+                            "[ ]"
+                            "{count}x"
+                        }
+                        Status::Todo(_) => "[ ]",
+                    }}
+                    "{name}"
+                </Block>
+            }}
+            "end"
+        </Block>
+    };
+    assert_eq!(
+        testing::snapshot(view, 12, 4),
+        "[x] milk    \n[ ] 2x eggs \n[ ] tea     \nend         \n"
+    );
+}
+
+#[test]
+fn test_slots() {
+    use mana_tui::testing;
+
+    let view = ui! {
+        <Block Width::fixed(12) Height::fixed(4)>
+            <Card>
+                <Card:footer>"(footer)"</Card:footer>
+                "body"
+                "text"
+                <Card:header>"title"</Card:header>
+            </Card>
+            <Row>"a" "b" "c"</Row>
+        </Block>
+    };
+    assert_eq!(
+        testing::snapshot(view, 12, 4),
+        "title       \nbody text   \n(footer)    \na b c       \n"
+    );
+}
+
+#[test]
+fn test_props() {
+    use mana_tui::testing;
+
+    let props = BadgeProps {
+        icon: '!',
+        ..Default::default()
+    };
+    let view = ui! {
+        <Block Width::fixed(10) Height::fixed(3)>
+            <Badge .label="plain" />
+            <Badge ..{props} .label="spread" />
+            <Badge .label="into" .suffix='s' />
+        </Block>
+    };
+    assert_eq!(
+        testing::snapshot(view, 10, 3),
+        "* plain   \n! spread  \n* intos   \n"
+    );
+}
+
+#[test]
+fn test_handlers() {
+    use mana_tui::mana_tui_beheaded::focus::{FocusPolicy, Keybind, OnClick, OnHover, OnPress};
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+    let mut ctx = ElementCtx::new();
+    let root = ctx.spawn_ui(ui! {
+        <Block>
+            <Block @click={|_| {}} @hover={|_| {}} @key:ctrl+s={|_| {}} />
+            <Text @click={|_| {}} FocusPolicy::Pass @key:shift+f={|_| {}}>"pass"</Text>
+        </Block>
+    });
+    let children = ctx.get::<&Children>(root).unwrap().to_vec();
+    let [handlers, pass] = children[..] else {
+        panic!("expected 2 children, got {children:?}");
+    };
+
+    assert!(ctx.get::<&OnClick>(handlers).is_ok());
+    assert!(ctx.get::<&OnHover>(handlers).is_ok());
+    assert!(ctx.get::<&OnPress>(handlers).is_ok());
+    assert!(matches!(
+        *ctx.get::<&FocusPolicy>(handlers).unwrap(),
+        FocusPolicy::Block
+    ));
+    let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+    assert!(
+        ctx.get::<&Keybind>(handlers)
+            .unwrap()
+            .event_eq(ctrl_s, KeyEventKind::Press)
+    );
+
+    // components come after handlers, so they win
+    assert!(matches!(
+        *ctx.get::<&FocusPolicy>(pass).unwrap(),
+        FocusPolicy::Pass
+    ));
+    let shift_f = KeyEvent::new(KeyCode::Char('F'), KeyModifiers::SHIFT);
+    assert!(
+        ctx.get::<&Keybind>(pass)
+            .unwrap()
+            .event_eq(shift_f, KeyEventKind::Press)
+    );
+}
+
+#[test]
+fn test_markup() {
+    use mana_tui::testing;
+
+    let count = 3;
+    let value = 7;
+    let view = ui! {
+        <Block Width::fixed(10) Height::fixed(4)>
+            <Text .markup>"[b]bold[/b] [fg=red]{count}[/]"</Text>
+            <Line .markup>"[[x] [y]"</Line>
+            // without `.markup` brackets are plain text
+            "[b] {value:[^3}"
+            // aliases close each other
+            <Line .markup>"[bold]x[/b] y"</Line>
+        </Block>
+    };
+    assert_eq!(
+        testing::styled_snapshot(view, 10, 4),
+        "bold 3    \n[x] [y]   \n[b] [7[   \nx y       \n\
+         --- styles ---\n0:0..4 +BOLD\n0:5..6 fg=Red\n3:0..1 +BOLD\n"
+    );
+}
@@ -0,0 +1,82 @@
+//! benchmarks the full update cycle of the runtime: update, view, despawn, spawn, layout and draw.
+//!
+//! run with `cargo bench -p mana-tui-potion`.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use mana_tui_elemental::prelude::*;
+use mana_tui_potion::{Ctx, Effect, update_cycle};
+use ratatui::{Terminal, backend::TestBackend};
+
+#[derive(Debug, Clone, Copy)]
+enum Msg {
+    Down,
+}
+
+#[derive(Debug, Clone)]
+struct Model {
+    rows: Vec<String>,
+    selected: usize,
+}
+
+async fn update(model: Model, msg: Msg) -> (Model, Effect<Msg>) {
+    match msg {
+        Msg::Down => (
+            Model {
+                selected: (model.selected + 1) % model.rows.len(),
+                ..model
+            },
+            Effect::none(),
+        ),
+    }
+}
+
+async fn view(model: &Model) -> View {
+    let row = |(idx, label): (usize, &String)| {
+        let marker = if idx == model.selected { ">" } else { " " };
+        ui(Block::new())
+            .with((Direction::Horizontal, Gap(1)))
+            .children((
+                ui(Text::raw(marker)).with((Width::fixed(1),)),
+                ui(Text::raw(format!("{idx}"))).with((Width::fixed(6),)),
+                ui(Text::raw(label.clone())).with((Width::grow(),)),
+            ))
+            .done()
+    };
+    ui(Block::bordered())
+        .with((Width::grow(), Height::grow()))
+        .children(model.rows.iter().enumerate().map(row))
+        .done()
+}
+
+fn bench_update_cycle(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let mut group = c.benchmark_group("update_cycle");
+    for rows in [100, 1000, 5000] {
+        group.bench_function(format!("table_{rows}"), |b| {
+            let mut ctx = Ctx::new(Terminal::new(TestBackend::new(120, 40)).unwrap());
+            let model = Model {
+                rows: (0..rows).map(|idx| format!("row {idx}")).collect(),
+                selected: 0,
+            };
+            let mut state = Some((model, None));
+            b.iter(|| {
+                let (model, prev_root) = state.take().unwrap();
+                let (model, _, root) = runtime.block_on(update_cycle(
+                    &mut ctx,
+                    model,
+                    Msg::Down,
+                    &view,
+                    &update,
+                    prev_root,
+                ));
+                state = Some((model, Some(root)));
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_update_cycle);
+criterion_main!(benches);
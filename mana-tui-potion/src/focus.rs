@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
+use crossterm::event::{Event, MouseEventKind};
 use hecs::{Entity, World};
-use mana_tui_elemental::layout::Children;
+use mana_tui_elemental::layout::{Children, is_hidden};
+use mana_tui_elemental::scroll::ScrollExt;
 use mana_tui_utils::resource::Resources;
+use ratatui::layout::{Offset, Position};
 
 use crate::{DefaultEvent, Effect};
 
@@ -45,6 +48,9 @@ pub(crate) fn generate_ui_stack(world: &mut World, root: Entity) {
 }
 
 pub(crate) fn generate_ui_stack_impl(world: &World, root: Entity, stack: &mut Vec<Entity>) {
+    if is_hidden(world, root) {
+        return;
+    }
     stack.push(root);
     let children = world.get::<&Children>(root);
     if let Ok(children) = children {
@@ -72,3 +78,23 @@ pub(crate) fn propagate_event<Msg: 'static, Model: 'static>(
     }
     Ok(None)
 }
+
+/// scrolls the container under the cursor when `event` is a mouse wheel event. returns whether
+/// anything scrolled, in which case the event is not propagated any further.
+pub(crate) fn scroll_wheel(world: &mut World, event: &DefaultEvent) -> bool {
+    let Event::Mouse(event) = event else {
+        return false;
+    };
+    let delta = match event.kind {
+        MouseEventKind::ScrollUp => Offset { x: 0, y: -1 },
+        MouseEventKind::ScrollDown => Offset { x: 0, y: 1 },
+        MouseEventKind::ScrollLeft => Offset { x: -1, y: 0 },
+        MouseEventKind::ScrollRight => Offset { x: 1, y: 0 },
+        _ => return false,
+    };
+    let position = Position {
+        x: event.column,
+        y: event.row,
+    };
+    world.scroll_at(position, delta)
+}
@@ -78,6 +78,17 @@ pub struct Ctx<B: Backend> {
     terminal: Terminal<B>,
 }
 
+impl<B: Backend> Ctx<B> {
+    /// create a context that draws to `terminal`.
+    #[must_use]
+    pub fn new(terminal: Terminal<B>) -> Self {
+        Self {
+            el_ctx: ElementCtx::new(),
+            terminal,
+        }
+    }
+}
+
 #[tailcall]
 async fn runtime<Msg: Clone + 'static, Model: 'static, B: 'static + ManaBackend>(
     model: Model,
@@ -92,13 +103,9 @@ async fn runtime<Msg: Clone + 'static, Model: 'static, B: 'static + ManaBackend>
     match msg {
         RuntimeMsg::App(msg) if quit_signal(&model, &msg) => Ok(()),
         RuntimeMsg::App(msg) => {
-            let (model, effect) = update(model, msg).await;
+            let (model, effect, root) =
+                update_cycle(ctx, model, msg, &view, &update, prev_root).await;
             tokio::spawn(effect.0.run_effect(msg_stream.dispatch.0.clone()));
-            let root = view(&model).await;
-            if let Some(prev) = prev_root {
-                ctx.despawn_ui(prev);
-            }
-            let root = render::<B>(ctx, root);
 
             runtime(
                 model,
@@ -111,8 +118,14 @@ async fn runtime<Msg: Clone + 'static, Model: 'static, B: 'static + ManaBackend>
             )
         }
         RuntimeMsg::Term(event) => {
-            let result = focus::propagate_event::<Msg, Model>(&ctx.el_ctx, &model, &event)?;
-            if let Some((msg, effect)) = result {
+            // the wheel scrolls the container under the cursor, which only needs a redraw
+            if focus::scroll_wheel(&mut ctx.el_ctx, &event) {
+                if let Some(root) = prev_root {
+                    draw(ctx, root);
+                }
+            } else if let Some((msg, effect)) =
+                focus::propagate_event::<Msg, Model>(&ctx.el_ctx, &model, &event)?
+            {
                 tokio::spawn(effect.0.run_effect(msg_stream.dispatch.0.clone()));
                 msg_stream
                     .dispatch
@@ -126,8 +139,35 @@ async fn runtime<Msg: Clone + 'static, Model: 'static, B: 'static + ManaBackend>
     }
 }
 
+/// runs one turn of the runtime: updates `model` with `msg`, replaces `prev_root` with the new
+/// view and draws it. returns the new model, the effect the update asked for and the new root.
+///
+/// [`run`] calls this for every message. it can also drive an app without an event loop, for
+/// example to measure it with ratatui's `TestBackend`.
+pub async fn update_cycle<Msg, Model, B: Backend>(
+    ctx: &mut Ctx<B>,
+    model: Model,
+    msg: Msg,
+    view: &impl ViewFn<Msg, Model>,
+    update: &impl UpdateFn<Msg, Model>,
+    prev_root: Option<Element>,
+) -> (Model, Effect<Msg>, Element) {
+    let (model, effect) = update(model, msg).await;
+    let root = view(&model).await;
+    if let Some(prev) = prev_root {
+        ctx.despawn_ui(prev);
+    }
+    let root = render::<B>(ctx, root);
+    (model, effect, root)
+}
+
 fn render<B: Backend>(ctx: &mut Ctx<B>, view: View) -> Element {
     let root = ctx.spawn_ui(view);
+    draw(ctx, root);
+    root
+}
+
+fn draw<B: Backend>(ctx: &mut Ctx<B>, root: Element) {
     let result = ctx.terminal.draw(|frame| {
         let result = ctx.el_ctx.calculate_layout(root, frame.area());
 
@@ -143,8 +183,6 @@ fn render<B: Backend>(ctx: &mut Ctx<B>, view: View) -> Element {
     if let Err(err) = result {
         tracing::error!("failed to draw: {err}");
     }
-
-    root
 }
 
 pub trait ManaBackend: Backend {
@@ -238,10 +276,7 @@ where
     let terminal = ratatui::Terminal::new(backend).map_err(|_| RuntimeErr::InitErr)?;
 
     ratatui::init();
-    let mut ctx = Ctx {
-        el_ctx: mana_tui_elemental::prelude::ElementCtx::new(),
-        terminal,
-    };
+    let mut ctx = Ctx::new(terminal);
 
     let (model, effect) = init().await;
     tokio::spawn(effect.0.run_effect(dispatch.0.clone()));
@@ -20,6 +20,8 @@ pub mod prelude {
 
 pub use mx_core as mx;
 
+pub mod testing;
+
 // TODO: lock behind crossterm feature
 #[macro_export]
 macro_rules! key {
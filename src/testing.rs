@@ -0,0 +1,210 @@
+//! # Testing
+//!
+//! headless rendering and snapshots for views.
+//!
+//! [`snapshot`] spawns a view in a fresh [`ElementCtx`], lays it out, renders it to a buffer of the
+//! given size and returns the buffer as text. [`styled_snapshot`] also lists the styles of the
+//! cells. use [`render_with`] to render in a context that holds resources like a
+//! [`Theme`][mana_tui_elemental::theme::Theme].
+//!
+//! [`assert_snapshot!`][crate::assert_snapshot] compares a snapshot with the one stored in
+//! `tests/snapshots/<name>.snap` and prints a line diff when they do not match. run the tests with
+//! `MANA_TUI_BLESS=1` to write new snapshots.
+//!
+//! # Example
+//!
+//! ```
+//! use mana_tui::prelude::*;
+//! use mana_tui::testing;
+//!
+//! let view = ui(Block::bordered()).with((Width::fixed(4), Height::fixed(3)));
+//! assert_eq!(testing::snapshot(view, 4, 3), "┌──┐\n│  │\n└──┘\n");
+//! ```
+
+use std::{fmt::Write, path::Path};
+
+use mana_tui_elemental::{layout::ElementCtx, ui::View, unicode};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+};
+
+/// environment variable that makes [`assert_snapshot`] write snapshots instead of comparing them.
+pub const BLESS_VAR: &str = "MANA_TUI_BLESS";
+
+/// spawns `view` in `ctx`, lays it out and renders it to a `width` by `height` buffer. the view is
+/// despawned afterwards, so `ctx` can render the next frame.
+pub fn render_with(ctx: &mut ElementCtx, view: impl Into<View>, width: u16, height: u16) -> Buffer {
+    let root = ctx.spawn_ui(view);
+    let mut buf = Buffer::empty(Rect::new(0, 0, width, height));
+    ctx.calculate_layout(root, buf.area)
+        .expect("the root element has the components layout needs");
+    ctx.render(root, buf.area, &mut buf);
+    ctx.despawn_ui(root);
+    buf
+}
+
+/// like [`render_with`], in a new context.
+pub fn render(view: impl Into<View>, width: u16, height: u16) -> Buffer {
+    render_with(&mut ElementCtx::new(), view, width, height)
+}
+
+/// renders `view` and returns its text.
+pub fn snapshot(view: impl Into<View>, width: u16, height: u16) -> String {
+    text(&render(view, width, height))
+}
+
+/// renders `view` and returns its text followed by its styles.
+pub fn styled_snapshot(view: impl Into<View>, width: u16, height: u16) -> String {
+    styled(&render(view, width, height))
+}
+
+/// the text of `buf`, one line per row. cells hidden by wide characters are skipped.
+pub fn text(buf: &Buffer) -> String {
+    let mut text = String::new();
+    for row in buf.content().chunks(usize::from(buf.area.width.max(1))) {
+        let mut skip = 0;
+        for cell in row {
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            text.push_str(cell.symbol());
+            skip = unicode::display_width(cell.symbol()).saturating_sub(1);
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// the text of `buf` followed by its styles. every run of cells with the same style that is not
+/// the default gets a line, like `1:3..6 fg=Red +BOLD` for the cells 3 to 5 of the second row.
+pub fn styled(buf: &Buffer) -> String {
+    let mut snapshot = text(buf);
+    let mut styles = String::new();
+    let width = usize::from(buf.area.width.max(1));
+    for (y, row) in buf.content().chunks(width).enumerate() {
+        let mut start = 0;
+        while start < row.len() {
+            let style = |idx: usize| (row[idx].fg, row[idx].bg, row[idx].modifier);
+            let end = (start..row.len())
+                .find(|&idx| style(idx) != style(start))
+                .unwrap_or(row.len());
+            let (fg, bg, modifier) = style(start);
+            if (fg, bg, modifier) != (Color::Reset, Color::Reset, Modifier::empty()) {
+                _ = write!(styles, "{y}:{start}..{end}");
+                if fg != Color::Reset {
+                    _ = write!(styles, " fg={fg:?}");
+                }
+                if bg != Color::Reset {
+                    _ = write!(styles, " bg={bg:?}");
+                }
+                for (name, _) in modifier.iter_names() {
+                    _ = write!(styles, " +{name}");
+                }
+                styles.push('\n');
+            }
+            start = end;
+        }
+    }
+    if !styles.is_empty() {
+        snapshot.push_str("--- styles ---\n");
+        snapshot.push_str(&styles);
+    }
+    snapshot
+}
+
+/// a line diff of two snapshots. lines only in `expected` start with `-`, lines only in `actual`
+/// with `+`.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+    // longest common subsequence, so a missing line does not mark every line after it
+    let mut lengths = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lengths[i][j] = if expected[i] == actual[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            _ = writeln!(diff, "  {}", expected[i]);
+            (i, j) = (i + 1, j + 1);
+        } else if j == actual.len()
+            || (i < expected.len() && lengths[i + 1][j] >= lengths[i][j + 1])
+        {
+            _ = writeln!(diff, "- {}", expected[i]);
+            i += 1;
+        } else {
+            _ = writeln!(diff, "+ {}", actual[j]);
+            j += 1;
+        }
+    }
+    diff
+}
+
+/// compares `actual` with the snapshot stored at `path`, or writes it there if [`BLESS_VAR`] is
+/// set. prefer the [`assert_snapshot!`][crate::assert_snapshot] macro, which picks the path.
+///
+/// # Panics
+///
+/// panics with a diff if the snapshots differ, or if there is no snapshot at `path` and
+/// [`BLESS_VAR`] is not set.
+pub fn assert_snapshot(path: impl AsRef<Path>, actual: &str) {
+    let path = path.as_ref();
+    let bless = std::env::var_os(BLESS_VAR).is_some_and(|value| value != "0");
+    let expected = std::fs::read_to_string(path).ok();
+    if expected.as_deref() == Some(actual) {
+        return;
+    }
+    if bless {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).expect("failed to create the snapshot directory");
+        }
+        std::fs::write(path, actual).expect("failed to write the snapshot");
+        return;
+    }
+    match expected {
+        Some(expected) => panic!(
+            "snapshot {} does not match:\n{}\nrun with {BLESS_VAR}=1 to update it",
+            path.display(),
+            diff(&expected, actual)
+        ),
+        None => panic!(
+            "snapshot {} does not exist, got:\n{actual}\nrun with {BLESS_VAR}=1 to write it",
+            path.display()
+        ),
+    }
+}
+
+/// compares a snapshot with `tests/snapshots/<name>.snap` in the crate that calls it. see the
+/// [`testing`][crate::testing] module.
+///
+/// # Example
+///
+/// ```no_run
+/// use mana_tui::prelude::*;
+/// use mana_tui::{assert_snapshot, testing};
+///
+/// let view = ui(Block::bordered().title_top("hi")).with((Width::fixed(6), Height::fixed(3)));
+/// assert_snapshot!("bordered", testing::styled_snapshot(view, 6, 3));
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $snapshot:expr $(,)?) => {
+        $crate::testing::assert_snapshot(
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("snapshots")
+                .join(format!("{}.snap", $name)),
+            &$snapshot,
+        )
+    };
+}